@@ -0,0 +1,614 @@
+use std::{collections::HashMap, fs, io::ErrorKind, str::FromStr};
+
+use serde::{Deserialize, Deserializer};
+use toml_edit::{table, value, DocumentMut};
+
+use crate::tetris::randomizer::RANDOMIZER_NAMES;
+
+use super::{gravity::GRAVITY_CURVE_NAMES, mode::{MODE_NAMES, SPRINT_GOALS}};
+
+pub const MIN_NEXT_COUNT: usize = 1;
+pub const MAX_NEXT_COUNT: usize = 6;
+
+// 盤面の最小サイズ (4列なら 4-wide の練習ができる)
+pub const MIN_BOARD_COLUMNS: usize = 4;
+pub const MIN_BOARD_ROWS: usize = 4;
+pub const MIN_HIDDEN_ROWS: usize = 2;
+pub const MIN_CELL_SIZE: i32 = 4;
+
+// ポリオミノの大きさの上限 (8 で 704 種類)
+pub const MAX_POLYOMINO_ORDER: usize = 8;
+
+// ユーザー設定ファイル (実行時のカレントディレクトリから読む)
+pub const USER_CONFIG_PATH: &str = "config.toml";
+
+// フレーム単位の設定は 60 FPS として ms に換算する
+pub const FRAME_MS: f64 = 1000.0 / 60.0;
+
+// ゲーム全体の設定 (設定ファイルを読み込み、コマンドライン引数で上書きできる)
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct GameConfig {
+    pub mode: String,
+    pub sprint_goal: u32,
+    // Dig で最初に積まれているゴミ行の数
+    pub dig_rows: usize,
+    // ゴミ行の穴の列が変わる確率 (0.0〜1.0)
+    pub garbage_messiness: f64,
+    // Puzzle で選ぶパズル集のファイル
+    pub puzzle_pack: String,
+    pub randomizer: String,
+    pub seed: Option<u64>,
+    // ピースセットのファイル (省略すると標準のテトリミノ)
+    pub piece_set: Option<String>,
+    // 指定するとその大きさのポリオミノをすべて使う (5 なら 18 種のペントミノ)
+    pub polyomino: Option<usize>,
+    pub next_count: usize,
+    pub show_ghost: bool,
+    pub lock_delay_ms: u32,
+    #[serde(deserialize_with = "deserialize_lock_resets")]
+    pub max_lock_resets: Option<u32>,
+    pub gravity_curve: String,
+    pub start_level: u32,
+    pub lines_per_level: u32,
+    pub handling: HandlingConfig,
+    pub board: BoardConfig,
+    // モードごとの上書き (例: [modes.sprint] next_count = 3)
+    pub modes: HashMap<String, ModeConfig>,
+    // 読み込んだ設定ファイル (設定画面で変えた項目はここに書き戻す)
+    #[serde(skip)]
+    pub path: String,
+}
+
+// ポーズメニューの設定画面で変えられる項目
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Setting {
+    Ghost,
+    Das,
+    Arr,
+}
+
+// モードごとに変えられる設定 (省略した項目は全体の設定を使う)
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ModeConfig {
+    pub next_count: Option<usize>,
+}
+
+// 操作感の設定 (DAS / ARR は ms の数値か "10f" のようなフレーム数で書ける)
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct HandlingConfig {
+    #[serde(rename = "das", deserialize_with = "deserialize_delay")]
+    pub das_ms: f64,
+    #[serde(rename = "arr", deserialize_with = "deserialize_delay")]
+    pub arr_ms: f64,
+    // 重力の何倍の速さでソフトドロップするか (inf で一瞬で落とす)
+    pub soft_drop_factor: f64,
+}
+
+// 盤面の大きさと出現位置 (ウィンドウと HUD の配置もここから決まる)
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct BoardConfig {
+    pub columns: usize,
+    // 見えている行数 (この上に hidden_rows 行の見えないバッファがある)
+    pub rows: usize,
+    pub hidden_rows: usize,
+    // 1マスの大きさ (px)。省略すると盤面の高さが 600px 前後になるように決める
+    pub cell_size: Option<i32>,
+    // 3マス幅のブロックを出す左端の列。省略すると中央 (10列なら 3)
+    pub spawn_column: Option<i32>,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig::new()
+    }
+}
+
+impl Default for HandlingConfig {
+    fn default() -> Self {
+        HandlingConfig {
+            das_ms: 10.0 * FRAME_MS,
+            arr_ms: 2.0 * FRAME_MS,
+            soft_drop_factor: 20.0,
+        }
+    }
+}
+
+impl Default for BoardConfig {
+    fn default() -> Self {
+        BoardConfig {
+            columns: 10,
+            rows: 20,
+            hidden_rows: 20,
+            cell_size: None,
+            spawn_column: None,
+        }
+    }
+}
+
+impl BoardConfig {
+    // "10x40" のような 列x行 の形式で盤面の大きさを設定する
+    pub fn set_size(&mut self, text: &str) -> bool {
+        let size = text
+            .split_once('x')
+            .and_then(|(columns, rows)| Some((columns.parse().ok()?, rows.parse().ok()?)));
+        match size {
+            Some((columns, rows)) => {
+                self.columns = columns;
+                self.rows = rows;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn spawn_column(&self) -> i32 {
+        self.spawn_column.unwrap_or((self.columns as i32 - 3) / 2)
+    }
+}
+
+impl GameConfig {
+    pub fn new() -> Self {
+        GameConfig {
+            mode: String::from("endless"),
+            sprint_goal: 40,
+            dig_rows: 10,
+            garbage_messiness: 1.0,
+            puzzle_pack: String::from("puzzles/basics.toml"),
+            randomizer: String::from("7-bag"),
+            seed: None,
+            piece_set: None,
+            polyomino: None,
+            next_count: 5,
+            show_ghost: true,
+            lock_delay_ms: 500,
+            max_lock_resets: Some(15),
+            gravity_curve: String::from("guideline"),
+            start_level: 1,
+            lines_per_level: 10,
+            handling: HandlingConfig::default(),
+            board: BoardConfig::default(),
+            modes: HashMap::new(),
+            path: String::from(USER_CONFIG_PATH),
+        }
+    }
+
+    // 設定ファイルを読み込む (ファイルがなければ既定値)
+    pub fn load(path: &str) -> Self {
+        let mut config = GameConfig::read(path);
+        config.path = String::from(path);
+        config
+    }
+
+    fn read(path: &str) -> Self {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(_) => return GameConfig::new(),
+        };
+
+        match toml::from_str::<GameConfig>(&text) {
+            Ok(mut config) => {
+                config.validate();
+                config
+            }
+            Err(err) => {
+                eprintln!("Failed to parse {}: {}", path, err);
+                GameConfig::new()
+            }
+        }
+    }
+
+    // 設定画面で変えた項目だけを設定ファイルに書き戻す (ほかの項目やコメントはそのまま残す)
+    // DAS / ARR は設定画面と同じくフレーム数で書く
+    pub fn save_setting(&self, setting: Setting) -> Result<(), String> {
+        let path = &self.path;
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(format!("Failed to read {}: {}", path, err)),
+        };
+        let mut document = text
+            .parse::<DocumentMut>()
+            .map_err(|err| format!("Failed to parse {}: {}", path, err))?;
+
+        if setting != Setting::Ghost && !document.contains_key("handling") {
+            document["handling"] = table();
+        }
+        match setting {
+            Setting::Ghost => document["show_ghost"] = value(self.show_ghost),
+            Setting::Das => document["handling"]["das"] = value(format_frames(self.handling.das_ms)),
+            Setting::Arr => document["handling"]["arr"] = value(format_frames(self.handling.arr_ms)),
+        }
+
+        fs::write(path, document.to_string()).map_err(|err| format!("Failed to write {}: {}", path, err))
+    }
+
+    // 例: tetris --config my.toml --randomizer tgm2 --seed 12345 --das 8f --gravity tgm
+    pub fn from_args() -> Self {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let path = args
+            .iter()
+            .position(|arg| arg == "--config")
+            .and_then(|index| args.get(index + 1))
+            .map_or(USER_CONFIG_PATH, String::as_str);
+
+        let mut config = GameConfig::load(path);
+        let mut args = args.iter().cloned();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--config" => {
+                    args.next();
+                }
+                "--mode" => {
+                    if let Some(name) = args.next() {
+                        config.set_mode(&name);
+                    }
+                }
+                "--sprint" => {
+                    config.set_mode("sprint");
+                    if let Some(goal) = parse_value(&arg, args.next()) {
+                        config.sprint_goal = goal;
+                    }
+                }
+                "--dig" => {
+                    config.set_mode("dig");
+                    if let Some(rows) = parse_value(&arg, args.next()) {
+                        config.dig_rows = rows;
+                    }
+                }
+                "--messiness" => {
+                    if let Some(messiness) = parse_value(&arg, args.next()) {
+                        config.garbage_messiness = messiness;
+                    }
+                }
+                "--survival" => config.set_mode("survival"),
+                "--puzzles" => {
+                    config.set_mode("puzzle");
+                    if let Some(path) = args.next() {
+                        config.puzzle_pack = path;
+                    }
+                }
+                "--ultra" => config.set_mode("ultra"),
+                "--marathon" => config.set_mode("marathon"),
+                "--randomizer" => {
+                    if let Some(name) = args.next() {
+                        config.set_randomizer(&name);
+                    }
+                }
+                "--seed" => {
+                    if let Some(seed) = parse_value(&arg, args.next()) {
+                        config.seed = Some(seed);
+                    }
+                }
+                "--pieces" => config.piece_set = args.next(),
+                "--pentomino" => config.polyomino = Some(5),
+                "--polyomino" => {
+                    if let Some(order) = parse_value(&arg, args.next()) {
+                        config.polyomino = Some(order);
+                    }
+                }
+                "--next" => {
+                    if let Some(count) = parse_value(&arg, args.next()) {
+                        config.set_next_count(count);
+                    }
+                }
+                "--no-ghost" => config.show_ghost = false,
+                "--lock-delay" => {
+                    if let Some(delay) = parse_value(&arg, args.next()) {
+                        config.lock_delay_ms = delay;
+                    }
+                }
+                // "infinite" を指定すると接地中の移動・回転で何度でもやり直せる
+                "--lock-resets" => match args.next().as_deref() {
+                    Some("infinite") => config.max_lock_resets = None,
+                    value => {
+                        if let Some(count) = parse_value(&arg, value.map(String::from)) {
+                            config.max_lock_resets = Some(count);
+                        }
+                    }
+                },
+                "--gravity" => {
+                    if let Some(name) = args.next() {
+                        config.set_gravity_curve(&name);
+                    }
+                }
+                "--level" => {
+                    if let Some(level) = parse_value(&arg, args.next()) {
+                        config.start_level = level;
+                    }
+                }
+                "--lines-per-level" => {
+                    if let Some(lines) = parse_value(&arg, args.next()) {
+                        config.lines_per_level = lines;
+                    }
+                }
+                "--board" => {
+                    let size = args.next().unwrap_or_default();
+                    if !config.board.set_size(&size) {
+                        eprintln!("Invalid value for --board: {} (use columns x rows like 10x20)", size);
+                    }
+                }
+                "--cell-size" => {
+                    if let Some(size) = parse_value(&arg, args.next()) {
+                        config.board.cell_size = Some(size);
+                    }
+                }
+                "--spawn-column" => {
+                    if let Some(column) = parse_value(&arg, args.next()) {
+                        config.board.spawn_column = Some(column);
+                    }
+                }
+                "--das" | "--arr" => match args.next().as_deref().and_then(parse_delay) {
+                    Some(delay) if arg == "--das" => config.handling.das_ms = delay,
+                    Some(delay) => config.handling.arr_ms = delay,
+                    None => eprintln!("Invalid value for {} (use milliseconds like 133 or frames like 8f)", arg),
+                },
+                "--sdf" => {
+                    if let Some(factor) = parse_value(&arg, args.next()) {
+                        config.handling.soft_drop_factor = factor;
+                    }
+                }
+                _ => eprintln!("Unknown argument: {}", arg),
+            }
+        }
+
+        config.validate();
+        config
+    }
+
+    // 範囲外や未知の値を既定値に戻す
+    fn validate(&mut self) {
+        let mode = std::mem::take(&mut self.mode);
+        self.mode = GameConfig::new().mode;
+        self.set_mode(&mode);
+
+        if !SPRINT_GOALS.contains(&self.sprint_goal) {
+            eprintln!("sprint_goal must be one of {:?}", SPRINT_GOALS);
+            self.sprint_goal = GameConfig::new().sprint_goal;
+        }
+
+        let randomizer = std::mem::take(&mut self.randomizer);
+        self.randomizer = GameConfig::new().randomizer;
+        self.set_randomizer(&randomizer);
+        self.set_next_count(self.next_count);
+        self.validate_modes();
+
+        let gravity_curve = std::mem::take(&mut self.gravity_curve);
+        self.gravity_curve = GameConfig::new().gravity_curve;
+        self.set_gravity_curve(&gravity_curve);
+
+        if self.lines_per_level == 0 {
+            eprintln!("lines_per_level must be greater than 0");
+            self.lines_per_level = GameConfig::new().lines_per_level;
+        }
+
+        self.validate_board();
+        self.validate_polyomino();
+        self.validate_garbage();
+
+        if self.handling.soft_drop_factor.is_nan() || self.handling.soft_drop_factor <= 0.0 {
+            eprintln!("soft_drop_factor must be greater than 0");
+            self.handling.soft_drop_factor = HandlingConfig::default().soft_drop_factor;
+        }
+    }
+
+    // 盤面はブロックが入る大きさ以上、出現位置は盤面の中に収める
+    fn validate_board(&mut self) {
+        let default = BoardConfig::default();
+        let board = &mut self.board;
+
+        if board.columns < MIN_BOARD_COLUMNS || board.rows < MIN_BOARD_ROWS {
+            eprintln!("Board must be at least {}x{}", MIN_BOARD_COLUMNS, MIN_BOARD_ROWS);
+            board.columns = default.columns;
+            board.rows = default.rows;
+        }
+        if board.hidden_rows < MIN_HIDDEN_ROWS {
+            eprintln!("hidden_rows must be at least {}", MIN_HIDDEN_ROWS);
+            board.hidden_rows = MIN_HIDDEN_ROWS;
+        }
+        if board.cell_size.is_some_and(|size| size < MIN_CELL_SIZE) {
+            eprintln!("cell_size must be at least {}", MIN_CELL_SIZE);
+            board.cell_size = None;
+        }
+        if let Some(column) = board.spawn_column {
+            let max_column = board.columns as i32 - 4;
+            if !(0..=max_column).contains(&column) {
+                eprintln!("spawn_column must be between 0 and {}", max_column);
+                board.spawn_column = None;
+            }
+        }
+    }
+
+    // 一番長いブロック (一直線の形) が横向きで入り、縦向きでも隠し行から出せる大きさまで
+    fn validate_polyomino(&mut self) {
+        let order = match self.polyomino {
+            Some(order) => order,
+            None => return,
+        };
+
+        let max_order = MAX_POLYOMINO_ORDER.min(self.board.columns).min(self.board.hidden_rows);
+        if !(1..=max_order).contains(&order) {
+            eprintln!("polyomino must be between 1 and {} for this board", max_order);
+            self.polyomino = None;
+        }
+    }
+
+    // ゴミ行は盤面に収まる数 (一番上の行は空けておく) まで
+    fn validate_garbage(&mut self) {
+        let max_rows = self.board.rows - 1;
+        if !(1..=max_rows).contains(&self.dig_rows) {
+            eprintln!("dig_rows must be between 1 and {} for this board", max_rows);
+            self.dig_rows = GameConfig::new().dig_rows.min(max_rows);
+        }
+        if !(0.0..=1.0).contains(&self.garbage_messiness) {
+            eprintln!("garbage_messiness must be between 0.0 and 1.0");
+            self.garbage_messiness = GameConfig::new().garbage_messiness;
+        }
+    }
+
+    // 未知のモード名は無視し、Next の数は 1〜6 に収める
+    fn validate_modes(&mut self) {
+        self.modes.retain(|name, _| {
+            let is_known = MODE_NAMES.contains(&name.as_str());
+            if !is_known {
+                eprintln!("Unknown mode in [modes]: {} (available: {})", name, MODE_NAMES.join(", "));
+            }
+            is_known
+        });
+        for mode in self.modes.values_mut() {
+            mode.next_count = mode.next_count.map(|count| count.clamp(MIN_NEXT_COUNT, MAX_NEXT_COUNT));
+        }
+    }
+
+    // そのモードで Next に表示するブロック数 ([modes.<name>] の指定があればそちら)
+    pub fn get_next_count(&self, mode: &str) -> usize {
+        self.modes
+            .get(mode)
+            .and_then(|mode| mode.next_count)
+            .unwrap_or(self.next_count)
+    }
+
+    // どのモードでも収まるように HUD の Next の枠を取る数
+    pub fn max_next_count(&self) -> usize {
+        self.modes
+            .values()
+            .filter_map(|mode| mode.next_count)
+            .fold(self.next_count, usize::max)
+    }
+
+    // Next に表示するブロック数 (1〜6) を設定する
+    pub fn set_next_count(&mut self, count: usize) {
+        self.next_count = count.clamp(MIN_NEXT_COUNT, MAX_NEXT_COUNT);
+    }
+
+    pub fn set_mode(&mut self, name: &str) {
+        if MODE_NAMES.contains(&name) {
+            self.mode = name.to_string();
+        } else {
+            eprintln!("Unknown mode: {} (available: {})", name, MODE_NAMES.join(", "));
+        }
+    }
+
+    pub fn set_randomizer(&mut self, name: &str) {
+        if RANDOMIZER_NAMES.contains(&name) {
+            self.randomizer = name.to_string();
+        } else {
+            eprintln!("Unknown randomizer: {} (available: {})", name, RANDOMIZER_NAMES.join(", "));
+        }
+    }
+
+    pub fn set_gravity_curve(&mut self, name: &str) {
+        if GRAVITY_CURVE_NAMES.contains(&name) {
+            self.gravity_curve = name.to_string();
+        } else {
+            eprintln!("Unknown gravity curve: {} (available: {})", name, GRAVITY_CURVE_NAMES.join(", "));
+        }
+    }
+}
+
+// 引数の値を数値などに変換する (失敗したらメッセージを出して無視する)
+fn parse_value<T: FromStr>(name: &str, value: Option<String>) -> Option<T> {
+    match value.as_deref().map(str::parse) {
+        Some(Ok(value)) => Some(value),
+        _ => {
+            eprintln!("Invalid value for {}: {}", name, value.unwrap_or_default());
+            None
+        }
+    }
+}
+
+// "133" / "133ms" はミリ秒、"8f" はフレーム数として ms に変換する
+pub fn parse_delay(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let (value, scale) = match text.strip_suffix('f') {
+        Some(frames) => (frames, FRAME_MS),
+        None => (text.strip_suffix("ms").unwrap_or(text), 1.0),
+    };
+
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|value| *value >= 0.0)
+        .map(|value| value * scale)
+}
+
+// ms を "10f" のようなフレーム数の表記にする
+fn format_frames(delay_ms: f64) -> String {
+    format!("{}f", (delay_ms / FRAME_MS).round())
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberOrText {
+    Number(f64),
+    Text(String),
+}
+
+fn deserialize_delay<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    match NumberOrText::deserialize(deserializer)? {
+        NumberOrText::Number(value) if value >= 0.0 => Ok(value),
+        NumberOrText::Number(value) => Err(serde::de::Error::custom(format!("invalid delay: {}", value))),
+        NumberOrText::Text(text) => parse_delay(&text)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid delay: {}", text))),
+    }
+}
+
+fn deserialize_lock_resets<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u32>, D::Error> {
+    match NumberOrText::deserialize(deserializer)? {
+        NumberOrText::Number(value) if value >= 0.0 => Ok(Some(value as u32)),
+        NumberOrText::Text(text) if text == "infinite" => Ok(None),
+        _ => Err(serde::de::Error::custom("max_lock_resets must be a count or \"infinite\"")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_delay_in_ms_and_frames() {
+        assert_eq!(parse_delay("133"), Some(133.0));
+        assert_eq!(parse_delay(" 133ms "), Some(133.0));
+        assert_eq!(parse_delay("8f"), Some(8.0 * FRAME_MS));
+        assert_eq!(parse_delay("0"), Some(0.0));
+        assert_eq!(parse_delay("-1"), None);
+        assert_eq!(parse_delay("fast"), None);
+    }
+
+    #[test]
+    fn reads_handling_from_toml() {
+        let handling: HandlingConfig = toml::from_str("das = \"8f\"\narr = 0").unwrap();
+        assert_eq!(handling.das_ms, 8.0 * FRAME_MS);
+        assert_eq!(handling.arr_ms, 0.0);
+        assert_eq!(handling.soft_drop_factor, HandlingConfig::default().soft_drop_factor);
+    }
+
+    #[test]
+    fn saves_changed_setting_only() {
+        let path = std::env::temp_dir().join(format!("tetris-settings-{}.toml", std::process::id()));
+        let path = path.to_str().unwrap();
+        fs::write(path, "# コメント\nshow_ghost = true\nnext_count = 3\n").unwrap();
+
+        let mut config = GameConfig::load(path);
+        config.show_ghost = false;
+        config.handling.das_ms = 8.0 * FRAME_MS;
+        config.handling.arr_ms = 0.0;
+        config.save_setting(Setting::Das).unwrap();
+        config.save_setting(Setting::Arr).unwrap();
+
+        let text = fs::read_to_string(path).unwrap();
+        let saved = GameConfig::load(path);
+        fs::remove_file(path).unwrap();
+
+        assert!(text.starts_with("# コメント\n"));
+        assert!(saved.show_ghost);
+        assert_eq!(saved.next_count, 3);
+        assert!((saved.handling.das_ms - 8.0 * FRAME_MS).abs() < 1e-9);
+        assert_eq!(saved.handling.arr_ms, 0.0);
+    }
+}
@@ -0,0 +1,994 @@
+use std::{collections::VecDeque, time::SystemTime};
+
+use rand::RngCore;
+
+use crate::{rand::SplitMixXoshiro256Rng, stage::{garbage::GarbageGenerator, grid::Grid}, tetris::{piece_set::PieceSet, randomizer::{create_randomizer, Randomizer}, spin::{detect_t_spin, SpinType}, tetris_block::TetrisBlock}};
+
+use super::{config::{GameConfig, FRAME_MS, MIN_NEXT_COUNT}, gravity::{gravity_interval_ms, gravity_per_frame}, mode::{GameMode, GameRecord}, puzzle::Puzzle, scoring::{drop_points, line_clear_points, LineClear}};
+
+// プレイヤー操作やタイマーから渡される抽象的な入力
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    SoftDrop,
+    HardDrop,
+    RotateClockwise,
+    RotateCounterClockwise,
+    Rotate180,
+    Hold,
+}
+
+// ゲームオーバーの原因 (ガイドラインのトップアウト条件)
+#[allow(clippy::enum_variant_names)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TopOut {
+    // 出現したブロックが積まれたブロックと重なった
+    BlockOut,
+    // ブロックがすべて見えている盤面より上で固定された
+    LockOut,
+    // ブロックの一部が見えている盤面より上で固定された
+    PartialLockOut,
+    // せり上がったゴミ行にブロックが盤面の一番上から押し出された
+    GarbageOut,
+}
+
+// T-Spin 判定のために覚えておく、最後に成功した動き
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LastMove {
+    Spawn,
+    Shift,
+    Drop,
+    Rotate { kick_index: usize, is_half_turn: bool },
+}
+
+// 1回の操作で起きたことをフロントエンドへ返す
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ActionResult {
+    pub moved: bool,
+    pub rotated: bool,
+    pub held: bool,
+    pub locked: bool,
+    pub lines_cleared: u32,
+    pub line_clear: Option<LineClear>,
+    pub points: u32,
+    pub game_over: bool,
+    pub finished: bool,
+}
+
+// 描画・音声に依存しないゲームロジック本体
+pub struct Engine {
+    pub grid: Grid,
+    pub piece_set: PieceSet,
+    // ピースセットのファイルが読めず標準のセットを使っているときの理由 (表示はフロントエンドに任せる)
+    pub piece_set_error: Option<String>,
+    pub blocks: Vec<TetrisBlock>,
+    pub config: GameConfig,
+    pub mode: GameMode,
+    pub seed: u64,
+    pub rng: SplitMixXoshiro256Rng,
+    pub randomizer: Box<dyn Randomizer>,
+    // Puzzle で遊んでいるパズル
+    pub puzzle: Option<Puzzle>,
+    // 決まった順番で出すブロックの番号 (Some ならランダマイザを使わず、なくなったら出さない)
+    pub piece_queue: Option<VecDeque<usize>>,
+    pub garbage: GarbageGenerator,
+    pub garbage_timer_ms: f64,
+    pub current_block: TetrisBlock,
+    pub next_blocks: VecDeque<TetrisBlock>,
+    pub hold_block: Option<TetrisBlock>,
+    pub can_hold: bool,
+    pub lock_timer_ms: Option<f64>,
+    pub lock_resets: u32,
+    pub lowest_row: i32,
+    pub last_move: LastMove,
+    pub gravity_rows: f64,
+    pub game_over: bool,
+    pub top_out: Option<TopOut>,
+    // モードの目標を達成して終わった (そのときの記録)
+    pub record: Option<GameRecord>,
+    pub elapsed_ms: f64,
+    pub pieces: u32,
+    pub score: u32,
+    pub level: u32,
+    pub lines: u32,
+    pub combo: Option<u32>,
+    pub back_to_back: bool,
+    pub garbage_cleared: u32,
+}
+
+impl Engine {
+    pub fn new(config: GameConfig) -> Self {
+        let (piece_set, piece_set_error) = Engine::load_piece_set(&config);
+        let blocks = piece_set.to_blocks();
+        let seed = Engine::get_seed(&config);
+        let mut rng = SplitMixXoshiro256Rng::new(seed);
+        let randomizer = Engine::create_randomizer(&config, &mut rng, &piece_set);
+        let garbage = Engine::create_garbage(&config, &mut rng);
+
+        let mut engine = Engine {
+            grid: Grid::new(config.board.rows, config.board.columns, config.board.hidden_rows),
+            current_block: blocks[0].clone(),
+            next_blocks: VecDeque::new(),
+            hold_block: None,
+            can_hold: true,
+            lock_timer_ms: None,
+            lock_resets: 0,
+            lowest_row: 0,
+            last_move: LastMove::Spawn,
+            gravity_rows: 0.0,
+            level: config.start_level,
+            lines: 0,
+            combo: None,
+            back_to_back: false,
+            piece_set,
+            piece_set_error,
+            blocks,
+            mode: GameMode::from_config(&config),
+            config,
+            seed,
+            rng,
+            randomizer,
+            puzzle: None,
+            piece_queue: None,
+            garbage,
+            garbage_timer_ms: 0.0,
+            game_over: false,
+            top_out: None,
+            record: None,
+            elapsed_ms: 0.0,
+            pieces: 0,
+            score: 0,
+            garbage_cleared: 0,
+        };
+        engine.add_starting_garbage();
+        engine.fill_next_blocks();
+        let block = engine.take_next_block().expect("Next queue has at least one block");
+        engine.spawn_block(block);
+        engine
+    }
+
+    // モードを選び直して始める (パズルは外す)
+    pub fn start_mode(&mut self, name: &str) {
+        self.config.set_mode(name);
+        self.puzzle = None;
+        self.reset();
+    }
+
+    // パズルの盤面とブロックの列で始め直す (やり直しの reset でも同じパズルになる)
+    pub fn start_puzzle(&mut self, puzzle: Puzzle) {
+        self.config.set_mode("puzzle");
+        self.puzzle = Some(puzzle);
+        self.reset();
+    }
+
+    pub fn apply(&mut self, action: Action) -> ActionResult {
+        let mut result = ActionResult::default();
+
+        if self.is_over() {
+            return self.finish_result(result);
+        }
+
+        match action {
+            Action::MoveLeft => result.moved = self.move_block_left(),
+            Action::MoveRight => result.moved = self.move_block_right(),
+            Action::SoftDrop => {
+                result.moved = self.move_block_down();
+                if result.moved {
+                    self.add_score(drop_points(1, false), &mut result);
+                }
+            }
+            Action::HardDrop => self.move_block_up(&mut result),
+            Action::RotateClockwise => result.rotated = self.rotate_block(TetrisBlock::rotate),
+            Action::RotateCounterClockwise => result.rotated = self.rotate_block(TetrisBlock::rotate_counter_clockwise),
+            Action::Rotate180 => result.rotated = self.rotate_block(TetrisBlock::rotate_half_turn),
+            Action::Hold => result.held = self.hold(),
+        }
+
+        if result.moved || result.rotated {
+            self.reset_lock_timer();
+        }
+
+        self.finish_result(result)
+    }
+
+    // 時間経過の処理 (重力で落とし、接地中のブロックは lock delay が過ぎたら固定する)
+    pub fn update(&mut self, delta_ms: f64) -> ActionResult {
+        let mut result = ActionResult::default();
+
+        if self.is_over() {
+            return self.finish_result(result);
+        }
+
+        // 制限時間のあるモード (Ultra) は時間切れで終わる
+        self.elapsed_ms += delta_ms;
+        if let Some(limit) = self.mode.time_limit_ms() {
+            if self.elapsed_ms >= limit {
+                self.elapsed_ms = limit;
+                self.finish(true);
+                return self.finish_result(result);
+            }
+        }
+
+        // Survival は一定時間ごとにゴミ行がせり上がる
+        if let Some(interval) = self.mode.garbage_interval_ms(self.elapsed_ms) {
+            self.garbage_timer_ms += delta_ms;
+            if self.garbage_timer_ms >= interval {
+                self.garbage_timer_ms -= interval;
+                self.rise_garbage();
+                if self.game_over {
+                    return self.finish_result(result);
+                }
+            }
+        }
+
+        self.apply_gravity(delta_ms, &mut result);
+
+        if self.is_grounded() {
+            let elapsed = self.lock_timer_ms.unwrap_or(0.0) + delta_ms;
+            self.lock_timer_ms = Some(elapsed);
+
+            if elapsed >= self.config.lock_delay_ms as f64 {
+                self.lock_block(&mut result);
+            }
+        } else {
+            self.lock_timer_ms = None;
+        }
+
+        self.finish_result(result)
+    }
+
+    // ゲームオーバーかモードの目標を達成したら、それ以降の操作は受け付けない
+    pub fn is_over(&self) -> bool {
+        self.game_over || self.record.is_some()
+    }
+
+    fn finish_result(&self, mut result: ActionResult) -> ActionResult {
+        result.game_over = self.game_over;
+        result.finished = self.record.is_some();
+        result
+    }
+
+    // 経過フレーム分の段数をためて、1段たまるごとに落とす (20G なら一気に着地する)
+    fn apply_gravity(&mut self, delta_ms: f64, result: &mut ActionResult) {
+        self.gravity_rows += delta_ms / FRAME_MS * self.gravity();
+
+        while self.gravity_rows >= 1.0 {
+            self.gravity_rows -= 1.0;
+            if !self.move_block_down() {
+                self.gravity_rows = 0.0;
+                break;
+            }
+            result.moved = true;
+        }
+    }
+
+    // 現在のレベルの重力 (1フレームあたりの段数)
+    pub fn gravity(&self) -> f64 {
+        gravity_per_frame(&self.config.gravity_curve, self.level)
+    }
+
+    // 現在のレベルで1段落ちるまでの時間 (ソフトドロップの速さの基準)
+    pub fn gravity_interval_ms(&self) -> f64 {
+        gravity_interval_ms(&self.config.gravity_curve, self.level)
+    }
+
+    // 接地中に移動・回転できたら lock delay をやり直す (回数には上限がある)
+    fn reset_lock_timer(&mut self) {
+        if self.lock_timer_ms.is_none() {
+            return;
+        }
+
+        if self.config.max_lock_resets.is_none_or(|max| self.lock_resets < max) {
+            self.lock_timer_ms = Some(0.0);
+            self.lock_resets += 1;
+        }
+    }
+
+    fn is_grounded(&self) -> bool {
+        let mut below = self.current_block.clone();
+        below.move_block(1, 0);
+        self.is_outside(&below) || !self.fits(&below)
+    }
+
+    fn move_block_left(&mut self) -> bool {
+        self.current_block.move_block(0, -1);
+        if self.is_block_outside() || !self.block_fits() {
+            self.current_block.move_block(0, 1);
+            return false;
+        }
+        self.last_move = LastMove::Shift;
+        true
+    }
+
+    fn move_block_right(&mut self) -> bool {
+        self.current_block.move_block(0, 1);
+        if self.is_block_outside() || !self.block_fits() {
+            self.current_block.move_block(0, -1);
+            return false;
+        }
+        self.last_move = LastMove::Shift;
+        true
+    }
+
+    // 接地していても固定はしない (固定は update の lock delay に任せる)
+    fn move_block_down(&mut self) -> bool {
+        self.current_block.move_block(1, 0);
+
+        if self.is_block_outside() || !self.block_fits() {
+            self.current_block.move_block(-1, 0);
+            return false;
+        }
+        self.last_move = LastMove::Drop;
+
+        // 最も低い位置を更新したら lock delay のリセット回数も戻す
+        if self.current_block.row_offset > self.lowest_row {
+            self.lowest_row = self.current_block.row_offset;
+            self.lock_resets = 0;
+        }
+        true
+    }
+
+    fn move_block_up(&mut self, result: &mut ActionResult) {
+        let ghost = self.get_ghost_block();
+        let rows = (ghost.row_offset - self.current_block.row_offset) as u32;
+        if rows > 0 {
+            self.last_move = LastMove::Drop;
+        }
+        self.current_block = ghost;
+        self.add_score(drop_points(rows, true), result);
+        self.lock_block(result);
+    }
+
+    // 操作中のブロックをそのまま落とした場合の着地位置 (ゴースト)
+    pub fn get_ghost_block(&self) -> TetrisBlock {
+        let mut ghost = self.current_block.clone();
+        loop {
+            ghost.move_block(1, 0);
+            if self.is_outside(&ghost) || !self.fits(&ghost) {
+                ghost.move_block(-1, 0);
+                break;
+            }
+        }
+        ghost
+    }
+
+    // SRS のキック表を順に試し、すべて失敗した場合のみ回転を取り消す
+    fn rotate_block(&mut self, rotate: fn(&mut TetrisBlock)) -> bool {
+        let from = self.current_block.rotation_state;
+        rotate(&mut self.current_block);
+        let to = self.current_block.rotation_state;
+
+        let kicks = self.piece_set.get(self.current_block.id).kicks.get_kicks(from, to).to_vec();
+        for (kick_index, (x, y)) in kicks.into_iter().enumerate() {
+            // 表は上方向が正なので行方向は符号を反転する
+            self.current_block.move_block(-y, x);
+            if !self.is_block_outside() && self.block_fits() {
+                let is_half_turn = (from + 2) % 4 == to;
+                self.last_move = LastMove::Rotate { kick_index, is_half_turn };
+                return true;
+            }
+            self.current_block.move_block(y, -x);
+        }
+
+        self.current_block.rotation_state = from;
+        false
+    }
+
+    // 操作中のブロックをホールドと入れ替える (初回は次のブロックを出す)
+    // 1回の落下につき1度だけで、向きと位置は出現時に戻す
+    fn hold(&mut self) -> bool {
+        if !self.can_hold {
+            return false;
+        }
+
+        let held = self.get_spawn_block(self.current_block.id);
+        let block = match self.hold_block.take().or_else(|| self.take_next_block()) {
+            Some(block) => block,
+            None => return false,
+        };
+        self.spawn_block(block);
+        self.hold_block = Some(held);
+        self.can_hold = false;
+        true
+    }
+
+    // 新しいブロックを出現させ、接地・固定の状態を初期化する
+    fn spawn_block(&mut self, mut block: TetrisBlock) {
+        let (row, column) = self.get_spawn_offset(&block);
+        block.move_block(row, column);
+        self.current_block = block;
+        self.lock_timer_ms = None;
+        self.lock_resets = 0;
+        self.lowest_row = self.current_block.row_offset;
+        self.last_move = LastMove::Spawn;
+        self.gravity_rows = 0.0;
+
+        if !self.block_fits() {
+            self.set_top_out(TopOut::BlockOut);
+            return;
+        }
+
+        // 下が空いていればすぐに1段落とす
+        if self.move_block_down() {
+            self.last_move = LastMove::Spawn;
+        }
+    }
+
+    // 出現時の向きの下端を見えている盤面のすぐ上に置き、横は spawn_column を基準に中央へ寄せる
+    // (10列なら 3マス幅は 3 列目から、I は 3 列目から、O は 4 列目から)
+    fn get_spawn_offset(&self, block: &TetrisBlock) -> (i32, i32) {
+        let tiles = &block.cells[block.rotation_state];
+        let max_row = tiles.iter().map(|tile| tile.row).max().unwrap_or(0);
+        let min_column = tiles.iter().map(|tile| tile.column).min().unwrap_or(0);
+        let max_column = tiles.iter().map(|tile| tile.column).max().unwrap_or(0);
+        let width = max_column - min_column + 1;
+
+        let row = self.grid.hidden_rows as i32 - 1 - max_row;
+        let column = self.config.board.spawn_column() + (4 - width).div_euclid(2) - min_column;
+        (row, column)
+    }
+
+    fn set_top_out(&mut self, reason: TopOut) {
+        self.game_over = true;
+        self.top_out = Some(reason);
+    }
+
+    fn get_spawn_block(&self, id: usize) -> TetrisBlock {
+        self.blocks
+            .iter()
+            .find(|block| block.id == id)
+            .expect("Every block id comes from Engine::blocks")
+            .clone()
+    }
+
+    fn lock_block(&mut self, result: &mut ActionResult) {
+        let spin = self.get_spin();
+        let tiles = self.current_block.get_cell_positions();
+
+        // 固定できるのは盤面内に収まっているブロックだけ (fits で確認済み)
+        for tile in &tiles {
+            self.grid.grid[tile.row as usize][tile.column as usize] = self.current_block.id as i32;
+        }
+        result.locked = true;
+        self.pieces += 1;
+
+        let hidden_rows = self.grid.hidden_rows as i32;
+        if tiles.iter().all(|tile| tile.row < hidden_rows) {
+            self.set_top_out(TopOut::LockOut);
+            return;
+        }
+        if tiles.iter().any(|tile| tile.row < hidden_rows) {
+            self.set_top_out(TopOut::PartialLockOut);
+            return;
+        }
+
+        self.garbage_cleared += self.grid.count_full_garbage_rows();
+        let rows_cleared = self.grid.clear_full_rows() as u32;
+        self.score_lock(rows_cleared, spin, result);
+        self.add_lines(rows_cleared);
+
+        if self.mode.is_goal_reached(&self.get_record(), result.line_clear.as_ref()) {
+            self.finish(true);
+            return;
+        }
+
+        // ライン消去後に次のブロックを出す (消える前の盤面で重なり判定をしない)
+        // 決まった列を使い切ったらホールドのブロックを出し、それもなければ未達成で終わる
+        let block = match self.take_next_block().or_else(|| self.hold_block.take()) {
+            Some(block) => block,
+            None => {
+                self.finish(false);
+                return;
+            }
+        };
+        self.spawn_block(block);
+        self.can_hold = true;
+    }
+
+    // 最後の動きが回転だった T (t_spin のブロック) のみ3コーナー判定をする
+    // (180 度回転では TST の例外を使わない)
+    fn get_spin(&self) -> SpinType {
+        if !self.piece_set.get(self.current_block.id).t_spin {
+            return SpinType::None;
+        }
+
+        match self.last_move {
+            LastMove::Rotate { kick_index, is_half_turn } => {
+                let kick_index = if is_half_turn { None } else { Some(kick_index) };
+                detect_t_spin(&self.grid, &self.current_block, kick_index)
+            }
+            _ => SpinType::None,
+        }
+    }
+
+    // 固定1回分の得点を計算し、コンボと Back-to-Back の状態を更新する
+    fn score_lock(&mut self, lines: u32, spin: SpinType, result: &mut ActionResult) {
+        if lines == 0 {
+            self.combo = None;
+            if spin == SpinType::None {
+                return;
+            }
+        } else {
+            self.combo = Some(self.combo.map_or(0, |combo| combo + 1));
+        }
+
+        let mut clear = LineClear {
+            lines,
+            spin,
+            combo: self.combo.unwrap_or(0),
+            back_to_back: false,
+            perfect_clear: lines > 0 && self.grid.is_empty(),
+        };
+        // ライン消去なしの T-Spin は Back-to-Back を途切れさせない
+        if lines > 0 {
+            clear.back_to_back = self.back_to_back && clear.is_difficult();
+            self.back_to_back = clear.is_difficult();
+        }
+
+        result.lines_cleared = lines;
+        result.line_clear = Some(clear);
+        self.add_score(line_clear_points(&clear, self.level), result);
+    }
+
+    pub fn is_block_outside(&self) -> bool {
+        self.is_outside(&self.current_block)
+    }
+
+    pub fn block_fits(&self) -> bool {
+        self.fits(&self.current_block)
+    }
+
+    fn is_outside(&self, block: &TetrisBlock) -> bool {
+        let tiles = block.get_cell_positions();
+
+        tiles.iter().any(|tile| {
+            let row = tile.row;
+            let column = tile.column;
+
+            if row < 0 || column < 0 {
+                return true;
+            }
+
+            let row = row as usize;
+            let column = column as usize;
+
+            self.grid.is_cell_outside(row, column)
+        })
+    }
+
+    fn fits(&self, block: &TetrisBlock) -> bool {
+        let tiles = block.get_cell_positions();
+        let rows = self.grid.rows();
+        let columns = self.grid.columns();
+
+        tiles.iter().all(|tile| {
+            let row = tile.row;
+            let column = tile.column;
+
+            // 隠し行より上を含め、グリッド外は不許容
+            if row < 0 || row >= rows as i32 || column < 0 || column >= columns as i32 {
+                return false;
+            }
+
+            let row = row as usize;
+            let column = column as usize;
+
+            self.grid.is_cell_empty(row, column)
+        })
+    }
+
+    // 現在までの記録
+    pub fn get_record(&self) -> GameRecord {
+        GameRecord {
+            time_ms: self.elapsed_ms,
+            pieces: self.pieces,
+            lines: self.lines,
+            level: self.level,
+            score: self.score,
+            garbage_cleared: self.garbage_cleared,
+            cleared: false,
+        }
+    }
+
+    // モードの終了条件を満たしたので記録を残して終える
+    fn finish(&mut self, cleared: bool) {
+        self.record = Some(GameRecord { cleared, ..self.get_record() });
+    }
+
+    fn add_score(&mut self, points: u32, result: &mut ActionResult) {
+        self.score += points;
+        result.points += points;
+    }
+
+    // 消したライン数を数え、lines_per_level ごとにレベルを上げる
+    fn add_lines(&mut self, lines_cleared: u32) {
+        if lines_cleared == 0 {
+            return;
+        }
+        self.lines += lines_cleared;
+        self.level = self.config.start_level + self.lines / self.config.lines_per_level;
+    }
+
+    fn get_time_seed() -> u64 {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .expect("Generate seed with UNIX_EPOCH")
+            .as_nanos() as u64
+    }
+
+    // ゲームごとのマスターシード (指定がなければ現在時刻から作る。再現用の表示はフロントエンドが self.seed から行う)
+    fn get_seed(config: &GameConfig) -> u64 {
+        config.seed.unwrap_or_else(Engine::get_time_seed)
+    }
+
+    // 乱数はすべてマスターシードの乱数生成器から派生させる
+    // ゴミ行の穴は Next とは別の乱数で決める (ゴミ行の有無で配られるブロックが変わらない)
+    fn create_garbage(config: &GameConfig, rng: &mut SplitMixXoshiro256Rng) -> GarbageGenerator {
+        GarbageGenerator::new(rng.next_u64(), config.board.columns, config.garbage_messiness)
+    }
+
+    // 開始時のゴミ行 (最初のブロックを出す前に積む。パズルは盤面をそのまま使う)
+    fn add_starting_garbage(&mut self) {
+        if self.puzzle.is_some() {
+            return;
+        }
+        for _ in 0..self.mode.starting_garbage() {
+            let hole_column = self.garbage.next_hole();
+            self.grid.add_garbage_row(hole_column);
+        }
+    }
+
+    // 盤面の下からゴミ行を1段せり上げる
+    // 操作中のブロックと重なったら一緒に押し上げ、盤面の外に出るならトップアウト
+    fn rise_garbage(&mut self) {
+        let hole_column = self.garbage.next_hole();
+        if self.grid.add_garbage_row(hole_column) {
+            self.set_top_out(TopOut::GarbageOut);
+            return;
+        }
+        if self.block_fits() {
+            return;
+        }
+
+        self.current_block.move_block(-1, 0);
+        if self.is_block_outside() || !self.block_fits() {
+            self.set_top_out(TopOut::GarbageOut);
+            return;
+        }
+        // 積みと一緒に上がったので、lock delay のリセット判定の基準も上げる
+        self.lowest_row -= 1;
+    }
+
+    fn create_randomizer(config: &GameConfig, rng: &mut SplitMixXoshiro256Rng, piece_set: &PieceSet) -> Box<dyn Randomizer> {
+        create_randomizer(&config.randomizer, rng.next_u64(), &piece_set.names())
+            .expect("Randomizer name is validated by GameConfig")
+    }
+
+    // ゲームが持ち続けるランダマイザから次のブロックを取り出す
+    fn get_random_block(&mut self) -> Option<TetrisBlock> {
+        let index = match &mut self.piece_queue {
+            Some(queue) => queue.pop_front()?,
+            None => self.randomizer.next(),
+        };
+        Some(self.blocks[index].clone())
+    }
+
+    // Next の列から先頭を取り出し、末尾をランダマイザで補充する
+    fn take_next_block(&mut self) -> Option<TetrisBlock> {
+        let block = self.next_blocks.pop_front();
+        self.fill_next_blocks();
+        block
+    }
+
+    fn fill_next_blocks(&mut self) {
+        while self.next_blocks.len() < self.next_count() {
+            match self.get_random_block() {
+                Some(block) => self.next_blocks.push_back(block),
+                None => break,
+            }
+        }
+    }
+
+    // 今のモード (パズル) で Next に表示するブロック数
+    // パズルの指定は HUD の枠に収まるように、設定の中で一番多い数までにする
+    pub fn next_count(&self) -> usize {
+        let puzzle_count = self.puzzle.as_ref().and_then(|puzzle| puzzle.next_count);
+        match puzzle_count {
+            Some(count) => count.clamp(MIN_NEXT_COUNT, self.config.max_next_count()),
+            None => self.config.get_next_count(&self.config.mode),
+        }
+    }
+
+    // パズルの盤面と決まったブロックの列を用意する (retain_playable で確認済み)
+    fn setup_puzzle(&mut self) {
+        let puzzle = match &self.puzzle {
+            Some(puzzle) => puzzle,
+            None => return,
+        };
+
+        let visible_rows = self.grid.num_rows - self.grid.hidden_rows;
+        let rows = puzzle
+            .get_rows(&self.piece_set, self.grid.num_cols, visible_rows)
+            .expect("Puzzle boards are checked when the pack is loaded");
+        let queue = puzzle.get_queue(&self.piece_set).expect("Puzzle queues are checked when the pack is loaded");
+
+        self.grid.fill_bottom_rows(&rows);
+        self.piece_queue = Some(queue.into());
+        self.mode = GameMode::Puzzle { goal: puzzle.goal };
+    }
+
+    // ポリオミノの指定があればそのセットを、ファイルが指定されていればそのセットを、
+    // どちらもなければ標準のテトリミノを使う (ファイルが読めなければ標準のセットと理由を返す)
+    fn load_piece_set(config: &GameConfig) -> (PieceSet, Option<String>) {
+        if let Some(order) = config.polyomino {
+            return (PieceSet::polyomino(order), None);
+        }
+
+        let path = match &config.piece_set {
+            Some(path) => path,
+            None => return (PieceSet::standard(), None),
+        };
+
+        match PieceSet::load(path) {
+            Ok(piece_set) => (piece_set, None),
+            Err(err) => (PieceSet::standard(), Some(err)),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.grid.init();
+        self.mode = GameMode::from_config(&self.config);
+        self.blocks = self.piece_set.to_blocks();
+        self.seed = Engine::get_seed(&self.config);
+        self.rng = SplitMixXoshiro256Rng::new(self.seed);
+        self.randomizer = Engine::create_randomizer(&self.config, &mut self.rng, &self.piece_set);
+        self.garbage = Engine::create_garbage(&self.config, &mut self.rng);
+        self.garbage_timer_ms = 0.0;
+        self.garbage_cleared = 0;
+        self.add_starting_garbage();
+        self.piece_queue = None;
+        self.setup_puzzle();
+        self.hold_block = None;
+        self.can_hold = true;
+        self.game_over = false;
+        self.top_out = None;
+        self.record = None;
+        self.elapsed_ms = 0.0;
+        self.pieces = 0;
+        self.score = 0;
+        self.level = self.config.start_level;
+        self.lines = 0;
+        self.combo = None;
+        self.back_to_back = false;
+
+        // 状態を戻してから出す (出現時の Block Out を消さない)
+        self.next_blocks.clear();
+        self.fill_next_blocks();
+        let block = self.take_next_block().expect("Next queue has at least one block");
+        self.spawn_block(block);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::puzzle::PuzzleGoal;
+
+    use super::*;
+
+    // シードを固定して毎回同じ並びで始める
+    fn create_engine() -> Engine {
+        let mut config = GameConfig::new();
+        config.seed = Some(12345);
+        Engine::new(config)
+    }
+
+    // 操作中のブロックを name のブロックに差し替え、向きと位置を決める
+    fn place_block(engine: &mut Engine, name: &str, rotation_state: usize, row: i32, column: i32) {
+        let index = engine.piece_set.names().iter().position(|&piece| piece == name).unwrap();
+        let mut block = engine.blocks[index].clone();
+        block.rotation_state = rotation_state;
+        block.row_offset = row;
+        block.column_offset = column;
+        engine.current_block = block;
+    }
+
+    #[test]
+    fn plays_without_window() {
+        let mut engine = create_engine();
+        let mut drops = 0;
+        while !engine.apply(Action::HardDrop).game_over {
+            drops += 1;
+            assert!(drops < 1000);
+        }
+
+        assert!(engine.is_over());
+        assert!(engine.top_out.is_some());
+        assert_eq!(engine.pieces, drops + 1);
+    }
+
+    #[test]
+    fn rotates_with_wall_kick() {
+        // 左の壁に付けた縦向きの T は、右に1マス蹴り出して回る
+        let mut engine = create_engine();
+        place_block(&mut engine, "T", 1, 10, -1);
+
+        assert!(engine.apply(Action::RotateClockwise).rotated);
+        assert_eq!(engine.current_block.rotation_state, 2);
+        assert_eq!(engine.current_block.column_offset, 0);
+        assert_eq!(engine.last_move, LastMove::Rotate { kick_index: 1, is_half_turn: false });
+    }
+
+    #[test]
+    fn keeps_rotation_when_no_kick_fits() {
+        let mut engine = create_engine();
+        place_block(&mut engine, "T", 0, 10, 3);
+
+        // ブロックのマス以外をすべて埋める
+        let cells = engine.current_block.get_cell_positions();
+        for row in 0..engine.grid.rows() {
+            for column in 0..engine.grid.columns() {
+                if !cells.iter().any(|cell| cell.row == row as i32 && cell.column == column as i32) {
+                    engine.grid.grid[row][column] = 1;
+                }
+            }
+        }
+
+        assert!(!engine.apply(Action::RotateClockwise).rotated);
+        assert_eq!(engine.current_block.rotation_state, 0);
+        assert_eq!(engine.current_block.row_offset, 10);
+        assert_eq!(engine.current_block.column_offset, 3);
+    }
+
+    #[test]
+    fn rotates_counter_clockwise() {
+        let mut engine = create_engine();
+        place_block(&mut engine, "T", 0, 10, 3);
+
+        assert!(engine.apply(Action::RotateCounterClockwise).rotated);
+        assert_eq!(engine.current_block.rotation_state, 3);
+        assert_eq!(engine.last_move, LastMove::Rotate { kick_index: 0, is_half_turn: false });
+    }
+
+    #[test]
+    fn rotates_half_turn_with_kick() {
+        // 床に置いた T は 180 度回すと床にめり込むので、1段上に蹴り上げる
+        let mut engine = create_engine();
+        let row = engine.grid.rows() as i32 - 2;
+        place_block(&mut engine, "T", 0, row, 3);
+
+        assert!(engine.apply(Action::Rotate180).rotated);
+        assert_eq!(engine.current_block.rotation_state, 2);
+        assert_eq!(engine.current_block.row_offset, row - 1);
+        assert_eq!(engine.last_move, LastMove::Rotate { kick_index: 1, is_half_turn: true });
+    }
+
+    #[test]
+    fn holds_once_per_drop() {
+        let mut engine = create_engine();
+        assert!(engine.apply(Action::Hold).held);
+        assert!(!engine.apply(Action::Hold).held);
+
+        assert!(engine.apply(Action::HardDrop).locked);
+        assert!(engine.apply(Action::Hold).held);
+    }
+
+    #[test]
+    fn locks_after_lock_delay() {
+        let mut engine = create_engine();
+        while engine.apply(Action::SoftDrop).moved {}
+
+        let lock_delay = engine.config.lock_delay_ms as f64;
+        assert!(!engine.update(lock_delay - 1.0).locked);
+        assert!(engine.update(1.0).locked);
+        assert_eq!(engine.pieces, 1);
+    }
+
+    #[test]
+    fn move_resets_lock_delay() {
+        let mut engine = create_engine();
+        while engine.apply(Action::SoftDrop).moved {}
+
+        let lock_delay = engine.config.lock_delay_ms as f64;
+        engine.update(lock_delay - 1.0);
+        assert!(engine.apply(Action::MoveLeft).moved);
+        assert!(!engine.update(lock_delay - 1.0).locked);
+        assert!(engine.update(1.0).locked);
+    }
+
+    // row 行目から下を左端の列だけ空けて埋める (ラインは消えない)
+    fn fill_from_row(engine: &mut Engine, row: usize) {
+        for row in row..engine.grid.rows() {
+            for column in 1..engine.grid.columns() {
+                engine.grid.grid[row][column] = 1;
+            }
+        }
+    }
+
+    #[test]
+    fn tops_out_with_lock_out() {
+        // 見えない行だけに固定した
+        let mut engine = create_engine();
+        let hidden_rows = engine.grid.hidden_rows;
+        fill_from_row(&mut engine, hidden_rows);
+        place_block(&mut engine, "T", 0, hidden_rows as i32 - 2, 3);
+
+        assert!(engine.apply(Action::HardDrop).game_over);
+        assert_eq!(engine.top_out, Some(TopOut::LockOut));
+    }
+
+    #[test]
+    fn tops_out_with_partial_lock_out() {
+        // 一部が見えない行にはみ出して固定した
+        let mut engine = create_engine();
+        let hidden_rows = engine.grid.hidden_rows;
+        fill_from_row(&mut engine, hidden_rows + 1);
+        place_block(&mut engine, "T", 0, hidden_rows as i32 - 1, 3);
+
+        assert!(engine.apply(Action::HardDrop).game_over);
+        assert_eq!(engine.top_out, Some(TopOut::PartialLockOut));
+    }
+
+    #[test]
+    fn tops_out_with_block_out() {
+        // 出現位置がふさがっていて次のブロックが出せない
+        let mut engine = create_engine();
+        fill_from_row(&mut engine, 0);
+
+        assert!(engine.apply(Action::Hold).game_over);
+        assert_eq!(engine.top_out, Some(TopOut::BlockOut));
+    }
+
+    #[test]
+    fn dig_starts_with_garbage_rows() {
+        let mut engine = create_engine();
+        engine.start_mode("dig");
+
+        let garbage_rows = (0..engine.grid.rows()).filter(|&row| engine.grid.is_garbage_row(row)).count();
+        assert_eq!(garbage_rows, engine.config.dig_rows);
+    }
+
+    #[test]
+    fn rising_garbage_pushes_block_up() {
+        let mut engine = create_engine();
+        let row = engine.grid.rows() as i32 - 2;
+        place_block(&mut engine, "T", 0, row, 3);
+
+        engine.rise_garbage();
+        assert!(!engine.game_over);
+        assert_eq!(engine.current_block.row_offset, row - 1);
+        assert!(engine.grid.is_garbage_row(engine.grid.rows() - 1));
+    }
+
+    #[test]
+    fn rising_garbage_tops_out() {
+        let mut engine = create_engine();
+        engine.grid.grid[0][0] = 1;
+
+        engine.rise_garbage();
+        assert!(engine.game_over);
+        assert_eq!(engine.top_out, Some(TopOut::GarbageOut));
+    }
+
+    #[test]
+    fn solves_puzzle_with_fixed_queue() {
+        let mut engine = create_engine();
+        let puzzle = Puzzle {
+            name: String::from("Tetris"),
+            board: vec![String::from("#########."); 4],
+            queue: vec![String::from("I")],
+            goal: PuzzleGoal::Lines { count: 4 },
+            next_count: None,
+        };
+        engine.start_puzzle(puzzle);
+        assert!(engine.grid.is_garbage_row(engine.grid.rows() - 4));
+
+        engine.apply(Action::RotateClockwise);
+        while engine.apply(Action::MoveRight).moved {}
+        let result = engine.apply(Action::HardDrop);
+
+        assert_eq!(result.lines_cleared, 4);
+        assert!(result.finished);
+        assert!(engine.record.is_some_and(|record| record.cleared));
+    }
+}
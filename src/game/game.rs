@@ -1,284 +1,631 @@
-use std::time::SystemTime;
-
-use rand::seq::SliceRandom;
 use raylib::prelude::*;
 
-use crate::{rand::SplitMixXoshiro256Rng, stage::grid::Grid, tetris::{blocks::*, tetris_block::TetrisBlock}};
+use crate::{stage::grid::{Grid, GARBAGE_CELL}, tetris::{spin::SpinType, tetris_block::TetrisBlock}, utils::colors::{get_cell_colors, GARBAGE_GREY}};
+
+use super::{config::{GameConfig, Setting, FRAME_MS}, engine::{Action, ActionResult, Engine}, hud::{draw_board_message, draw_callouts, draw_centered_text, draw_hud, draw_menu, get_top_out_text}, input::{AutoRepeat, HeldKeys}, layout::Layout, mode::{get_mode_description, MODE_NAMES, SPRINT_GOALS}, puzzle::PuzzlePack, scoring::LineClear, state::GameState};
+
+const CALLOUT_DURATION_MS: f64 = 1500.0;
+
+const PAUSE_ITEMS: [&str; 4] = ["RESUME", "RESTART", "SETTINGS", "QUIT TO TITLE"];
 
+// 設定画面の項目と、DAS / ARR の上限 (フレーム数)
+const SETTINGS: [Setting; 3] = [Setting::Ghost, Setting::Das, Setting::Arr];
+const MAX_DAS_FRAMES: f64 = 20.0;
+const MAX_ARR_FRAMES: f64 = 10.0;
+
+// raylib のフロントエンド (入力・描画・音声) を担当し、ロジックは Engine に任せる
+// 入力と描画は GameState ごとに分ける
 pub struct Game<'a> {
-    pub grid: Grid,
-    pub blocks: Vec<TetrisBlock>,
-    pub current_block: TetrisBlock,
-    pub next_block: TetrisBlock,
-    pub game_over: bool,
-    pub score: u32,
+    pub engine: Engine,
+    pub state: GameState,
+    pub auto_repeat: AutoRepeat,
+    pub callouts: Vec<String>,
+    pub callout_timer_ms: f64,
+    pub layout: Layout,
+    // Puzzle のパズル集 (Puzzle を選んだときに読み込む)
+    pub puzzle_pack: Option<PuzzlePack>,
+    pub font: Font,
+    pub colors: Vec<Color>,
     pub music: Music<'a>,
     pub rotate_sound: Sound<'a>,
     pub clear_sound: Sound<'a>,
 }
 
 impl<'a> Game<'a> {
-    pub fn new(audio: &'a RaylibAudio) -> Self {
+    pub fn new(audio: &'a RaylibAudio, font: Font, config: GameConfig) -> Self {
         let mut music = audio.new_music("sounds/A-Type.mp3").expect("Failed to load A-Type.mp3");
         let rotate_sound = audio.new_sound("sounds/rotate.mp3").expect("Failed to load rotate.mp3");
         let clear_sound = audio.new_sound("sounds/clear.mp3").expect("Failed to load clear.mp3");
 
         music.play_stream();
 
+        let layout = Layout::new(&config);
+        let engine = Engine::new(config);
+        if let Some(err) = &engine.piece_set_error {
+            eprintln!("{} (using the standard pieces)", err);
+        } else if engine.config.polyomino.is_some() || engine.config.piece_set.is_some() {
+            println!("Pieces: {} ({} pieces)", engine.piece_set.name, engine.piece_set.pieces.len());
+        }
+
         Game {
-            grid: Grid::new(),
-            blocks: Game::get_all_blocks(),
-            current_block: Game::get_random_block(),
-            next_block: Game::get_random_block(),
-            game_over: false,
-            score: 0,
+            colors: get_cell_colors(&engine.piece_set),
+            engine,
+            state: GameState::Title,
+            auto_repeat: AutoRepeat::new(),
+            callouts: Vec::new(),
+            callout_timer_ms: 0.0,
+            layout,
+            puzzle_pack: None,
+            font,
             music,
             rotate_sound,
             clear_sound,
         }
     }
 
+    // パズル集を読み込む (読めなければメッセージを出して None)
+    fn load_puzzle_pack(engine: &Engine) -> Option<PuzzlePack> {
+        let board = &engine.config.board;
+        let mut pack = match PuzzlePack::load(&engine.config.puzzle_pack) {
+            Ok(pack) => pack,
+            Err(err) => {
+                eprintln!("{}", err);
+                return None;
+            }
+        };
+        pack.retain_playable(&engine.piece_set, board.columns, board.rows);
+        if pack.puzzles.is_empty() {
+            eprintln!("No playable puzzles in {}", engine.config.puzzle_pack);
+            return None;
+        }
+        Some(pack)
+    }
+
     pub fn handle_input(&mut self, rl: &mut RaylibHandle) {
         // キー入力の取得 (そのままOption<KeyboardKey>として扱う)
         let key_pressed = rl.get_key_pressed();
-    
-        // ゲームオーバー処理
-        if self.game_over {
-            if let Some(key) = key_pressed { // Optionを直接利用
-                if key != KeyboardKey::KEY_NULL {
-                    self.music.stop_stream();
-                    self.game_over = false;
-                    self.reset();
-                }
-            }
-            return; // ゲームオーバー後は処理終了
+
+        // ウィンドウが裏に回ったら自動でポーズする
+        if !rl.is_window_focused() && matches!(self.state, GameState::Playing | GameState::Countdown { .. }) {
+            self.pause();
+            return;
         }
 
-        let key_down = rl.is_key_down(KeyboardKey::KEY_DOWN);
+        match self.state {
+            GameState::Title => self.handle_title_input(key_pressed),
+            GameState::ModeSelect { selected } => self.handle_mode_select_input(key_pressed, selected),
+            GameState::PuzzleSelect { selected } => self.handle_puzzle_select_input(key_pressed, selected),
+            GameState::Countdown { .. } => {}
+            GameState::Playing => self.handle_playing_input(rl, key_pressed),
+            GameState::Paused { selected } => self.handle_paused_input(key_pressed, selected),
+            GameState::Settings { selected } => self.handle_settings_input(key_pressed, selected),
+            GameState::GameOver => self.handle_game_over_input(key_pressed),
+            GameState::Results => self.handle_results_input(key_pressed),
+        }
+    }
 
-        // move down continually while pressing ↓ button
-        if key_down {
-            self.move_block_down();
-            self.update_score(0, 1);
+    fn handle_title_input(&mut self, key_pressed: Option<KeyboardKey>) {
+        if let Some(KeyboardKey::KEY_ENTER) | Some(KeyboardKey::KEY_SPACE) = key_pressed {
+            self.open_mode_select();
         }
-    
-        // 入力処理
-        if let Some(key) = key_pressed { // Optionを直接利用
-            match key {
-                KeyboardKey::KEY_LEFT => self.move_block_left(),
-                KeyboardKey::KEY_RIGHT => self.move_block_right(),
-                // KeyboardKey::KEY_DOWN => {
-                //     self.move_block_down();
-                //     self.update_score(0, 1);
-                // },
-                KeyboardKey::KEY_UP => self.move_block_up(),
-                KeyboardKey::KEY_R => self.rotate_block(),
-                _ => {}
+    }
+
+    // 今の設定のモードを選んだ状態で開く
+    fn open_mode_select(&mut self) {
+        let selected = MODE_NAMES.iter().position(|&name| name == self.engine.config.mode).unwrap_or(0);
+        self.state = GameState::ModeSelect { selected };
+    }
+
+    // 上下で選び、Enter で始める (Puzzle はパズルの選択へ進む)
+    fn handle_mode_select_input(&mut self, key_pressed: Option<KeyboardKey>, selected: usize) {
+        let count = MODE_NAMES.len();
+        match key_pressed {
+            Some(KeyboardKey::KEY_UP) => self.state = GameState::ModeSelect { selected: (selected + count - 1) % count },
+            Some(KeyboardKey::KEY_DOWN) => self.state = GameState::ModeSelect { selected: (selected + 1) % count },
+            Some(KeyboardKey::KEY_ENTER) | Some(KeyboardKey::KEY_SPACE) => {
+                let name = MODE_NAMES[selected];
+                if name == "puzzle" {
+                    self.open_puzzle_select();
+                } else {
+                    self.engine.start_mode(name);
+                    self.start_countdown();
+                }
             }
+            Some(KeyboardKey::KEY_LEFT) if MODE_NAMES[selected] == "sprint" => self.change_sprint_goal(-1),
+            Some(KeyboardKey::KEY_RIGHT) if MODE_NAMES[selected] == "sprint" => self.change_sprint_goal(1),
+            Some(KeyboardKey::KEY_BACKSPACE) => self.state = GameState::Title,
+            _ => {}
         }
     }
 
-    pub fn draw(&self, rd: &mut RaylibDrawHandle) {
-        self.grid.draw(rd);
-        self.current_block.draw(11, 11, rd);
+    // Sprint の目標ライン数を 20 / 40 / 100 から選ぶ
+    fn change_sprint_goal(&mut self, step: isize) {
+        let config = &mut self.engine.config;
+        let count = SPRINT_GOALS.len() as isize;
+        let index = SPRINT_GOALS.iter().position(|&goal| goal == config.sprint_goal).unwrap_or(0) as isize;
+        config.sprint_goal = SPRINT_GOALS[(index + step).rem_euclid(count) as usize];
+    }
 
-        match self.next_block.id {
-            3 => self.next_block.draw( 255, 290, rd),
-            4 => self.next_block.draw( 255, 280, rd),
-            _ => self.next_block.draw( 270, 270, rd)
+    fn open_puzzle_select(&mut self) {
+        if self.puzzle_pack.is_none() {
+            self.puzzle_pack = Game::load_puzzle_pack(&self.engine);
+        }
+        if self.puzzle_pack.is_some() {
+            self.state = GameState::PuzzleSelect { selected: 0 };
         }
     }
 
-    pub fn move_block_left(&mut self) {
-        if !self.game_over {
-            self.current_block.move_block(0, -1);
-            if self.is_block_outside() || !self.block_fits() {
-                self.current_block.move_block(0, 1);
+    // 上下で選び、Enter でそのパズルを始める
+    fn handle_puzzle_select_input(&mut self, key_pressed: Option<KeyboardKey>, selected: usize) {
+        let pack = match &self.puzzle_pack {
+            Some(pack) => pack,
+            None => return,
+        };
+        let count = pack.puzzles.len();
+
+        match key_pressed {
+            Some(KeyboardKey::KEY_UP) => self.state = GameState::PuzzleSelect { selected: (selected + count - 1) % count },
+            Some(KeyboardKey::KEY_DOWN) => self.state = GameState::PuzzleSelect { selected: (selected + 1) % count },
+            Some(KeyboardKey::KEY_ENTER) | Some(KeyboardKey::KEY_SPACE) => {
+                let puzzle = pack.puzzles[selected].clone();
+                self.engine.start_puzzle(puzzle);
+                self.start_countdown();
             }
+            Some(KeyboardKey::KEY_BACKSPACE) => self.open_mode_select(),
+            _ => {}
         }
     }
 
-    pub fn move_block_right(&mut self) {
-        if !self.game_over {
-            self.current_block.move_block(0, 1);
-            if self.is_block_outside() || !self.block_fits() {
-                self.current_block.move_block(0, -1);
+    fn handle_playing_input(&mut self, rl: &mut RaylibHandle, key_pressed: Option<KeyboardKey>) {
+        // F4 でいつでもすぐにやり直す (Sprint の練習用)
+        if key_pressed == Some(KeyboardKey::KEY_F4) {
+            self.restart();
+            return;
+        }
+
+        // 左右移動 (DAS / ARR) とソフトドロップの連続入力
+        let held = HeldKeys {
+            left: rl.is_key_down(KeyboardKey::KEY_LEFT),
+            right: rl.is_key_down(KeyboardKey::KEY_RIGHT),
+            soft_drop: rl.is_key_down(KeyboardKey::KEY_DOWN),
+        };
+        let delta_ms = rl.get_frame_time() as f64 * 1000.0;
+        let actions = self.auto_repeat.update(held, &self.engine.config.handling, self.engine.gravity_interval_ms(), delta_ms);
+        for (action, count) in actions {
+            for _ in 0..count {
+                if self.state != GameState::Playing || !self.apply(action).moved {
+                    break;
+                }
             }
         }
-    }
+        if self.state != GameState::Playing {
+            return;
+        }
 
-    pub fn move_block_down(&mut self) {
-        if !self.game_over {
-            self.current_block.move_block(1, 0);
+        // 入力処理
+        if let Some(key) = key_pressed { // Optionを直接利用
+            let action = match key {
+                KeyboardKey::KEY_UP => Action::HardDrop,
+                KeyboardKey::KEY_X | KeyboardKey::KEY_R => Action::RotateClockwise,
+                KeyboardKey::KEY_Z => Action::RotateCounterClockwise,
+                KeyboardKey::KEY_A => Action::Rotate180,
+                KeyboardKey::KEY_C | KeyboardKey::KEY_LEFT_SHIFT => Action::Hold,
+                KeyboardKey::KEY_G => {
+                    self.engine.config.show_ghost = !self.engine.config.show_ghost;
+                    return;
+                }
+                KeyboardKey::KEY_P | KeyboardKey::KEY_ESCAPE => {
+                    self.pause();
+                    return;
+                }
+                _ => return,
+            };
+            self.apply(action);
+        }
+    }
 
-            if self.is_block_outside() || !self.block_fits() {
-                self.current_block.move_block(-1, 0);
+    fn pause(&mut self) {
+        self.music.pause_stream();
+        self.state = GameState::Paused { selected: 0 };
+    }
 
-                if self.current_block.get_cell_positions().iter().any(|tile| tile.row < 0) {
-                    return;
+    // 上下で選び、Enter で決める (P / Esc はそのまま再開)
+    fn handle_paused_input(&mut self, key_pressed: Option<KeyboardKey>, selected: usize) {
+        let count = PAUSE_ITEMS.len();
+        match key_pressed {
+            Some(KeyboardKey::KEY_UP) => self.state = GameState::Paused { selected: (selected + count - 1) % count },
+            Some(KeyboardKey::KEY_DOWN) => self.state = GameState::Paused { selected: (selected + 1) % count },
+            Some(KeyboardKey::KEY_ENTER) | Some(KeyboardKey::KEY_SPACE) => match selected {
+                0 => self.resume(),
+                1 => {
+                    self.engine.reset();
+                    self.start_countdown();
                 }
+                2 => self.state = GameState::Settings { selected: 0 },
+                _ => {
+                    self.music.resume_stream();
+                    self.state = GameState::Title;
+                }
+            },
+            Some(KeyboardKey::KEY_P) | Some(KeyboardKey::KEY_ESCAPE) => self.resume(),
+            _ => {}
+        }
+    }
+
+    // 3-2-1 のカウントダウンを挟んで再開する
+    fn resume(&mut self) {
+        self.music.resume_stream();
+        self.auto_repeat = AutoRepeat::new();
+        self.state = GameState::countdown();
+    }
 
-                self.lock_block();
+    // 上下で選び、左右で値を変える
+    fn handle_settings_input(&mut self, key_pressed: Option<KeyboardKey>, selected: usize) {
+        match key_pressed {
+            Some(KeyboardKey::KEY_UP) => self.state = GameState::Settings { selected: (selected + SETTINGS.len() - 1) % SETTINGS.len() },
+            Some(KeyboardKey::KEY_DOWN) => self.state = GameState::Settings { selected: (selected + 1) % SETTINGS.len() },
+            Some(KeyboardKey::KEY_LEFT) => self.change_setting(selected, -1.0),
+            Some(KeyboardKey::KEY_RIGHT) | Some(KeyboardKey::KEY_ENTER) => self.change_setting(selected, 1.0),
+            Some(KeyboardKey::KEY_BACKSPACE) | Some(KeyboardKey::KEY_ESCAPE) | Some(KeyboardKey::KEY_P) => {
+                self.state = GameState::Paused { selected: 2 };
             }
+            _ => {}
         }
     }
 
-    pub fn move_block_up(&mut self) {
-        if !self.game_over {
-            loop {
-                self.current_block.move_block(1, 0);
-                if self.is_block_outside() || !self.block_fits() {
-                    self.current_block.move_block(-1, 0);
-                    break;
+    // DAS / ARR は1フレームずつ変え、変えた項目は設定ファイルに保存する
+    fn change_setting(&mut self, selected: usize, step: f64) {
+        let config = &mut self.engine.config;
+        let change_frames = |delay_ms: f64, max_frames: f64| ((delay_ms / FRAME_MS).round() + step).clamp(0.0, max_frames) * FRAME_MS;
+
+        let setting = SETTINGS[selected];
+        match setting {
+            Setting::Ghost => config.show_ghost = !config.show_ghost,
+            Setting::Das => config.handling.das_ms = change_frames(config.handling.das_ms, MAX_DAS_FRAMES),
+            Setting::Arr => config.handling.arr_ms = change_frames(config.handling.arr_ms, MAX_ARR_FRAMES),
+        }
+
+        if let Err(err) = config.save_setting(setting) {
+            eprintln!("{}", err);
+        }
+    }
+
+    // 余計なキーで最後の画面が消えないように、Enter でだけ結果へ進む
+    fn handle_game_over_input(&mut self, key_pressed: Option<KeyboardKey>) {
+        match key_pressed {
+            Some(KeyboardKey::KEY_ENTER) => self.state = GameState::Results,
+            Some(KeyboardKey::KEY_F4) => self.restart(),
+            _ => {}
+        }
+    }
+
+    // Enter で同じモード (パズル) をやり直し、Backspace で選択画面に戻る
+    fn handle_results_input(&mut self, key_pressed: Option<KeyboardKey>) {
+        match key_pressed {
+            Some(KeyboardKey::KEY_ENTER) => {
+                self.engine.reset();
+                self.start_countdown();
+            }
+            Some(KeyboardKey::KEY_F4) => self.restart(),
+            Some(KeyboardKey::KEY_BACKSPACE) => {
+                if self.engine.puzzle.is_some() {
+                    self.open_puzzle_select();
+                } else {
+                    self.open_mode_select();
                 }
             }
-            self.lock_block();
+            _ => {}
         }
     }
 
-    pub fn rotate_block(&mut self) {
-        if !self.game_over {
-            self.current_block.rotate();
-            if self.is_block_outside() || !self.block_fits() {
-                self.current_block.undo_rotation();
-            } else {
-                self.rotate_sound.play();
+    // 操作を Engine に渡し、結果に応じて効果音と表示を更新する
+    pub fn apply(&mut self, action: Action) -> ActionResult {
+        let result = self.engine.apply(action);
+        self.handle_result(&result);
+        result
+    }
+
+    // 時間経過を Engine に渡す (遊んでいる間だけ進める)
+    pub fn update(&mut self, delta_ms: f64) {
+        self.callout_timer_ms = (self.callout_timer_ms - delta_ms).max(0.0);
+        if self.callout_timer_ms == 0.0 {
+            self.callouts.clear();
+        }
+
+        match self.state {
+            GameState::Countdown { remaining_ms } => {
+                let remaining_ms = remaining_ms - delta_ms;
+                self.state = if remaining_ms > 0.0 {
+                    GameState::Countdown { remaining_ms }
+                } else {
+                    GameState::Playing
+                };
+            }
+            GameState::Playing => {
+                let result = self.engine.update(delta_ms);
+                self.handle_result(&result);
             }
+            _ => {}
         }
     }
 
-    pub fn lock_block(&mut self) {
-        let tiles = self.current_block.get_cell_positions();
+    // 効果音と消し方の表示を更新し、終わったら GameOver / Results へ移る
+    fn handle_result(&mut self, result: &ActionResult) {
+        if result.rotated {
+            self.rotate_sound.play();
+        }
+        if result.lines_cleared > 0 {
+            self.clear_sound.play();
+        }
+        if let Some(clear) = &result.line_clear {
+            self.callouts = get_callouts(clear);
+            self.callout_timer_ms = CALLOUT_DURATION_MS;
+        }
 
-        for tile in tiles {
-            let row = tile.row;
-            let column = tile.column;
+        if result.game_over {
+            self.music.stop_stream();
+            self.state = GameState::GameOver;
+        } else if result.finished {
+            self.music.stop_stream();
+            self.state = GameState::Results;
+        }
+    }
 
-            // 負の座標
-            if row < 0 || column < 0 {
-                eprintln!("Invalid tile position during lock_block: {:?}", tile);
-                continue;
+    pub fn draw(&self, rd: &mut RaylibDrawHandle) {
+        rd.clear_background(Color::DARKBLUE);
+
+        match self.state {
+            GameState::Title => self.draw_title(rd),
+            GameState::ModeSelect { selected } => self.draw_mode_select(rd, selected),
+            GameState::PuzzleSelect { selected } => self.draw_puzzle_select(rd, selected),
+            GameState::Countdown { remaining_ms } => {
+                self.draw_game(rd);
+                let count = format!("{}", (remaining_ms / 1000.0).ceil() as u32);
+                draw_board_message(rd, &self.font, &self.layout, &count, Color::GOLD, &[]);
             }
+            GameState::Playing => self.draw_game(rd),
+            // ポーズ中は盤面と Next を隠して、先を読めないようにする
+            GameState::Paused { selected } => {
+                let items: Vec<String> = PAUSE_ITEMS.iter().map(|item| item.to_string()).collect();
+                let footer = [String::from("ENTER: select  ESC: resume")];
+                draw_menu(rd, &self.font, &self.layout, "PAUSED", &items, selected, &footer);
+            }
+            GameState::Settings { selected } => self.draw_settings(rd, selected),
+            GameState::GameOver => {
+                self.draw_game(rd);
+                let lines = [String::from(get_top_out_text(self.engine.top_out)), String::from("ENTER: results")];
+                draw_board_message(rd, &self.font, &self.layout, "GAME OVER", Color::WHITE, &lines);
+            }
+            GameState::Results => {
+                self.draw_game(rd);
+                self.draw_results(rd);
+            }
+        }
+    }
+
+    fn draw_title(&self, rd: &mut RaylibDrawHandle) {
+        let center_x = self.layout.width as f32 / 2.0;
+        let center_y = self.layout.height as f32 / 2.0;
+        draw_centered_text(rd, &self.font, "TETRIS", center_x, center_y - 60.0, 76, Color::GOLD);
+        draw_centered_text(rd, &self.font, "Press ENTER", center_x, center_y + 40.0, 30, Color::WHITE);
+    }
+
+    fn draw_mode_select(&self, rd: &mut RaylibDrawHandle, selected: usize) {
+        let config = &self.engine.config;
+        let items: Vec<String> = MODE_NAMES
+            .iter()
+            .map(|&name| match name {
+                "sprint" => format!("SPRINT < {} >", config.sprint_goal),
+                _ => name.to_uppercase(),
+            })
+            .collect();
+        let controls = match MODE_NAMES[selected] {
+            "sprint" => "LEFT/RIGHT: goal  ENTER: start",
+            _ => "ENTER: start  BACKSPACE: back",
+        };
+        let footer = [
+            get_mode_description(MODE_NAMES[selected], config),
+            String::from(controls),
+        ];
+        draw_menu(rd, &self.font, &self.layout, "MODE", &items, selected, &footer);
+    }
+
+    fn draw_settings(&self, rd: &mut RaylibDrawHandle, selected: usize) {
+        let config = &self.engine.config;
+        let items = [
+            format!("GHOST: {}", if config.show_ghost { "ON" } else { "OFF" }),
+            format!("DAS: {}f", (config.handling.das_ms / FRAME_MS).round()),
+            format!("ARR: {}f", (config.handling.arr_ms / FRAME_MS).round()),
+        ];
+        let footer = [
+            format!("Saved to {}", config.path),
+            String::from("LEFT/RIGHT: change  BACKSPACE: back"),
+        ];
+        draw_menu(rd, &self.font, &self.layout, "SETTINGS", &items, selected, &footer);
+    }
+
+    fn draw_puzzle_select(&self, rd: &mut RaylibDrawHandle, selected: usize) {
+        let pack = match &self.puzzle_pack {
+            Some(pack) => pack,
+            None => return,
+        };
+        let items: Vec<String> = pack.puzzles.iter().map(|puzzle| puzzle.name.clone()).collect();
+        let footer = [
+            pack.puzzles[selected].goal.describe(),
+            String::from("ENTER: start  BACKSPACE: back"),
+        ];
+        draw_menu(rd, &self.font, &self.layout, &pack.name, &items, selected, &footer);
+    }
 
-            let row = row as usize;
-            let column = column as usize;
+    // HUD と盤面
+    fn draw_game(&self, rd: &mut RaylibDrawHandle) {
+        draw_hud(rd, &self.font, &self.engine, &self.layout);
+        self.draw_board(rd);
+        draw_callouts(rd, &self.font, &self.callouts, &self.layout);
+    }
+
+    // モードごとの結果 (トップアウトしたときはその時点の記録)
+    fn draw_results(&self, rd: &mut RaylibDrawHandle) {
+        let record = self.engine.record.unwrap_or_else(|| self.engine.get_record());
+        let (title, mut lines) = self.engine.mode.get_results(&record);
+        lines.push(String::from("ENTER: retry"));
+        lines.push(String::from("BACKSPACE: menu"));
+        draw_board_message(rd, &self.font, &self.layout, title, Color::GOLD, &lines);
+    }
+
+    fn draw_board(&self, rd: &mut RaylibDrawHandle) {
+        // 隠し行は描画しないので、見えている最上段が盤面の上端になるようにずらす
+        let layout = &self.layout;
+        let hidden_rows = self.engine.grid.hidden_rows as i32;
+        let offset_y = layout.board_y - hidden_rows * layout.cell_size;
 
-            self.grid.grid[row][column] = self.current_block.id as i32;
+        self.draw_grid(&self.engine.grid, rd);
+        if self.engine.config.show_ghost {
+            self.draw_ghost(&self.engine.get_ghost_block(), layout.board_x, offset_y, rd);
         }
+        self.draw_block(&self.engine.current_block, layout.board_x, offset_y, rd);
 
-        self.current_block = self.next_block.clone();
-        if !self.block_fits() {
-            self.game_over = true;
+        if let Some(hold_block) = &self.engine.hold_block {
+            self.draw_preview(hold_block, layout.panel_center_x(), layout.hold.center_y(), layout.hold.height - 10, rd);
         }
 
-        self.next_block = Game::get_random_block();
-        let rows_cleared = self.grid.clear_full_rows();
-        if rows_cleared > 0 {
-            self.clear_sound.play();
-            self.update_score(rows_cleared.try_into().unwrap(), 0);
+        // Next は縦に並べる
+        for (index, block) in self.engine.next_blocks.iter().enumerate() {
+            let center_y = layout.next_slot_center_y(index);
+            self.draw_preview(block, layout.panel_center_x(), center_y, layout.preview_slot_height - 6, rd);
         }
     }
 
-    pub fn is_block_outside(&self) -> bool {
-        let tiles = self.current_block.get_cell_positions();
+    // Next / Hold の枠内に、出現時の向きのブロックを中心座標に合わせて描画する
+    // 大きなブロック (ペントミノなど) は max_height と枠の幅に収まるようにマスを小さくする
+    fn draw_preview(&self, block: &TetrisBlock, center_x: i32, center_y: i32, max_height: i32, rd: &mut RaylibDrawHandle) {
+        let tiles = &block.cells[block.rotation_state];
+        let min_row = tiles.iter().map(|tile| tile.row).min().unwrap_or(0);
+        let max_row = tiles.iter().map(|tile| tile.row).max().unwrap_or(0);
+        let min_column = tiles.iter().map(|tile| tile.column).min().unwrap_or(0);
+        let max_column = tiles.iter().map(|tile| tile.column).max().unwrap_or(0);
+
+        let rows = max_row - min_row + 1;
+        let columns = max_column - min_column + 1;
+        let max_width = self.layout.panel_width - 20;
+        let cell_size = self.layout.preview_cell_size.min(max_width / columns).min(max_height / rows).max(2);
+
+        let width = columns * cell_size;
+        let height = rows * cell_size;
+        let offset_x = center_x - width / 2 - min_column * cell_size;
+        let offset_y = center_y - height / 2 - min_row * cell_size;
+
+        for tile in tiles {
+            rd.draw_rectangle(
+                tile.column * cell_size + offset_x,
+                tile.row * cell_size + offset_y,
+                cell_size - 1,
+                cell_size - 1,
+                self.colors[block.id]
+            );
+        }
+    }
 
-        tiles.iter().any(|tile| {
-            let row = tile.row;
-            let column = tile.column;
+    fn draw_grid(&self, grid: &Grid, rd: &mut RaylibDrawHandle) {
+        let cell_size = self.layout.cell_size;
+        for row in grid.hidden_rows..grid.num_rows {
+            for col in 0..grid.num_cols {
+                let cell_value = grid.grid[row][col];
+                let x = col as i32 * cell_size + self.layout.board_x;
+                let y = (row - grid.hidden_rows) as i32 * cell_size + self.layout.board_y;
 
-            if row < 0 || column < 0 {
-                eprintln!("Block outside grid: {:?}", tile);
-                return true;
+                rd.draw_rectangle(x, y, cell_size - 1, cell_size - 1, self.get_cell_color(cell_value));
             }
+        }
+    }
 
-            let row = row as usize;
-            let column = column as usize;
+    // ゴミ行のマスはピースセットとは別の色で描く
+    fn get_cell_color(&self, cell_value: i32) -> Color {
+        if cell_value == GARBAGE_CELL {
+            GARBAGE_GREY
+        } else {
+            self.colors[cell_value as usize]
+        }
+    }
 
-            self.grid.is_cell_outside(row, column)
-        })
+    fn draw_block(&self, block: &TetrisBlock, offset_x: i32, offset_y: i32, rd: &mut RaylibDrawHandle) {
+        let cell_size = self.layout.cell_size;
+        let hidden_rows = self.engine.grid.hidden_rows as i32;
+        let tiles = block.get_cell_positions();
+        for tile in tiles.iter().filter(|tile| tile.row >= hidden_rows) {
+            rd.draw_rectangle(
+                tile.column * cell_size + offset_x,
+                tile.row * cell_size + offset_y,
+                cell_size - 1,
+                cell_size - 1,
+                self.colors[block.id]
+            );
+        }
     }
 
-    pub fn block_fits(&self) -> bool {
-        let tiles = self.current_block.get_cell_positions();
-        let rows = self.grid.rows();
-        let columns = self.grid.columns();
+    // ゴーストは半透明の塗りと枠線で描画する
+    fn draw_ghost(&self, block: &TetrisBlock, offset_x: i32, offset_y: i32, rd: &mut RaylibDrawHandle) {
+        let color = self.colors[block.id];
+        let cell_size = self.layout.cell_size;
+        let hidden_rows = self.engine.grid.hidden_rows as i32;
+        for tile in block.get_cell_positions().iter().filter(|tile| tile.row >= hidden_rows) {
+            let x = tile.column * cell_size + offset_x;
+            let y = tile.row * cell_size + offset_y;
+            rd.draw_rectangle(x, y, cell_size - 1, cell_size - 1, color.fade(0.25));
+            rd.draw_rectangle_lines(x, y, cell_size - 1, cell_size - 1, color);
+        }
+    }
 
-        tiles.iter().all(|tile| {
-            let row = tile.row;
-            let column = tile.column;
-    
-            // 負の座標は「一時的な許容」として扱う
-            if row < 0 {
-                return true; // 上部から降ってくる途中は許容する
-            }
-    
-            if row >= rows as i32 || column < 0 || column >= columns as i32 {
-                return false; // グリッド外に完全に出た場合は不許容
-            }
-    
-            let row = row as usize;
-            let column = column as usize;
-    
-            self.grid.is_cell_empty(row, column)
-        })
-    }
-
-    pub fn update_score(&mut self, lines_cleared: u32, move_down_points: u32) {
-        self.score += match lines_cleared {
-            1 => 100,
-            2 => 300,
-            3 => 500,
-            _ => 0,
-        };
-        self.score += move_down_points;
-    }
-
-    fn get_random_block() -> TetrisBlock {
-        let seed = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .expect("Generate seed with UNIX_EPOCH")
-            .as_nanos() as u64;
-        let mut rng = SplitMixXoshiro256Rng::new(seed);
-    
-        // 各ブロックのインスタンスを作成
-        let block_types = vec![
-            BlockType::IBlock(IBlock::new()),
-            BlockType::JBlock(JBlock::new()),
-            BlockType::LBlock(LBlock::new()),
-            BlockType::OBlock(OBlock::new()),
-            BlockType::SBlock(SBlock::new()),
-            BlockType::TBlock(TBlock::new()),
-            BlockType::ZBlock(ZBlock::new()),
-        ];
-    
-        // ランダムに1つのブロックを選択
-        let block_type = block_types.choose(&mut rng).unwrap(); // クローンを使ってインスタンスを取得
-        block_type.to_tetris_block() // 新規作成したブロックを返す
-    }
-
-    fn get_all_blocks() -> Vec<TetrisBlock> {
-        let block_types = vec![
-            BlockType::IBlock(IBlock::new()),
-            BlockType::JBlock(JBlock::new()),
-            BlockType::LBlock(LBlock::new()),
-            BlockType::OBlock(OBlock::new()),
-            BlockType::SBlock(SBlock::new()),
-            BlockType::TBlock(TBlock::new()),
-            BlockType::ZBlock(ZBlock::new()),
-        ];
-        block_types.into_iter().map(|block| block.to_tetris_block()).collect()
+    // カウントダウンから始める (Engine は始める状態にしておく)
+    fn start_countdown(&mut self) {
+        self.prepare_play();
+        self.state = GameState::countdown();
+    }
+
+    // カウントダウンなしですぐにやり直す
+    fn restart(&mut self) {
+        self.engine.reset();
+        self.prepare_play();
+        self.state = GameState::Playing;
     }
 
-    pub fn reset(&mut self) {
-        self.grid.init();
+    // 同じ並びで遊び直せるように、始めるたびにシードを表示する
+    fn prepare_play(&mut self) {
+        println!("Seed: {}", self.engine.seed);
+        self.auto_repeat = AutoRepeat::new();
+        self.callouts.clear();
+        self.music.stop_stream();
         self.music.play_stream();
-        self.blocks = Game::get_all_blocks();
-        self.current_block = Game::get_random_block();
-        self.next_block = Game::get_random_block();
-        self.score = 0;
     }
+}
 
-    // pub fn spawn_block(&mut self) {
-    //     self.current_block.position = Position { row: 0, column: 3 }; // 初期位置を確保
-    // }
-}
\ No newline at end of file
+// 消し方の表示 (例: "B2B", "T-SPIN MINI", "DOUBLE", "2 COMBO")
+fn get_callouts(clear: &LineClear) -> Vec<String> {
+    let mut callouts = Vec::new();
+
+    if clear.back_to_back {
+        callouts.push(String::from("B2B"));
+    }
+    match clear.spin {
+        SpinType::Full => callouts.push(String::from("T-SPIN")),
+        SpinType::Mini => callouts.push(String::from("T-SPIN MINI")),
+        SpinType::None => {}
+    }
+    let lines = match clear.lines {
+        0 => None,
+        1 => Some("SINGLE"),
+        2 => Some("DOUBLE"),
+        3 => Some("TRIPLE"),
+        _ => Some("TETRIS"),
+    };
+    if let Some(lines) = lines {
+        callouts.push(String::from(lines));
+    }
+    if clear.combo > 0 {
+        callouts.push(format!("{} COMBO", clear.combo));
+    }
+    if clear.perfect_clear {
+        callouts.push(String::from("PERFECT CLEAR"));
+    }
+    callouts
+}
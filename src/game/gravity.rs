@@ -0,0 +1,60 @@
+use super::config::FRAME_MS;
+
+// 設定やコマンドラインで指定できる重力カーブ名
+pub const GRAVITY_CURVE_NAMES: [&str; 3] = ["guideline", "nes", "tgm"];
+
+// 1フレームで落ちる段数の上限 (20G = 出現した瞬間に着地する)
+pub const MAX_GRAVITY: f64 = 20.0;
+
+// NES: レベル 0〜28 で1段落ちるまでのフレーム数 (29 以上は 1)
+const NES_FRAMES_PER_ROW: [u32; 29] = [
+    48, 43, 38, 33, 28, 23, 18, 13, 8, 6,
+    5, 5, 5, 4, 4, 4, 3, 3, 3, 2,
+    2, 2, 2, 2, 2, 2, 2, 2, 2,
+];
+
+// TGM: 内部レベルごとの重力 (1/256 G 単位、そのレベル以上で適用)
+const TGM_GRAVITY: [(u32, u32); 30] = [
+    (0, 4), (30, 6), (35, 8), (40, 10), (50, 12), (60, 16), (70, 32), (80, 48), (90, 64), (100, 80),
+    (120, 96), (140, 112), (160, 128), (170, 144), (200, 4), (220, 32), (230, 64), (233, 96), (236, 128), (239, 160),
+    (243, 192), (247, 224), (251, 256), (300, 512), (330, 768), (360, 1024), (400, 1280), (420, 1024), (450, 768), (500, 5120),
+];
+
+// TGM の内部レベル (0〜999) はこのゲームの1レベルを 50 として換算する (レベル 11 で 20G)
+const TGM_LEVEL_SCALE: u32 = 50;
+
+// 1フレーム (1/60 秒) あたりに落ちる段数 (G)
+pub fn gravity_per_frame(curve: &str, level: u32) -> f64 {
+    let gravity = match curve {
+        "nes" => 1.0 / NES_FRAMES_PER_ROW.get(level as usize).copied().unwrap_or(1) as f64,
+        "tgm" => {
+            let internal_level = level.saturating_sub(1).saturating_mul(TGM_LEVEL_SCALE);
+            let (_, gravity) = TGM_GRAVITY
+                .iter()
+                .rev()
+                .find(|(from, _)| internal_level >= *from)
+                .copied()
+                .unwrap_or(TGM_GRAVITY[0]);
+            gravity as f64 / 256.0
+        }
+        _ => guideline_gravity(level),
+    };
+    gravity.min(MAX_GRAVITY)
+}
+
+// 1段落ちるまでの時間 (ms)
+pub fn gravity_interval_ms(curve: &str, level: u32) -> f64 {
+    FRAME_MS / gravity_per_frame(curve, level)
+}
+
+// ガイドラインの式: 1段あたり (0.8 - (level - 1) * 0.007)^(level - 1) 秒
+fn guideline_gravity(level: u32) -> f64 {
+    let level = level.max(1) as i32;
+    let base = 0.8 - (level - 1) as f64 * 0.007;
+    if base <= 0.0 {
+        return MAX_GRAVITY;
+    }
+
+    let seconds_per_row = base.powi(level - 1);
+    FRAME_MS / (seconds_per_row * 1000.0)
+}
@@ -0,0 +1,99 @@
+use raylib::prelude::*;
+
+use super::{engine::{Engine, TopOut}, layout::{Layout, PanelBox, CALLOUT_LINE_HEIGHT}, mode::format_time};
+
+// Score / Hold / Next の列と、Level / Lines / Time の列を描画する (位置は Layout が決める)
+pub fn draw_hud(rd: &mut RaylibDrawHandle, font: &Font, engine: &Engine, layout: &Layout) {
+    let mode = engine.mode;
+    let score_text = format!("{}", engine.score);
+    let level_text = format!("{}", engine.level);
+    let lines_text = mode.get_lines_text(&engine.get_record());
+    let time_text = format_time(mode.display_time_ms(engine.elapsed_ms));
+
+    // Next の枠は表示する個数に合わせて伸ばす
+    let next = PanelBox { height: layout.next_height(engine.next_count()), ..layout.next };
+
+    let panel_x = layout.panel_x as f32;
+    let side_panel_x = layout.side_panel_x as f32;
+    let panel_width = layout.panel_width as f32;
+
+    draw_panel(rd, font, "Score", &layout.score, panel_x, panel_width, Some((&score_text, 38)));
+    draw_panel(rd, font, "Hold", &layout.hold, panel_x, panel_width, None);
+    draw_panel(rd, font, "Next", &next, panel_x, panel_width, None);
+    draw_panel(rd, font, "Level", &layout.level, side_panel_x, panel_width, Some((&level_text, 38)));
+    draw_panel(rd, font, "Lines", &layout.lines, side_panel_x, panel_width, Some((&lines_text, 38)));
+    draw_panel(rd, font, "Time", &layout.time, side_panel_x, panel_width, Some((&time_text, 30)));
+}
+
+// 見出しと枠、枠の中央に数値 (あれば) を描画する
+fn draw_panel(rd: &mut RaylibDrawHandle, font: &Font, label: &str, panel: &PanelBox, x: f32, width: f32, value: Option<(&str, i32)>) {
+    let center_x = x + width / 2.0;
+    draw_centered_text(rd, font, label, center_x, panel.label_y as f32, 38, Color::WHITE);
+    rd.draw_rectangle_rounded(Rectangle { x, y: panel.y as f32, width, height: panel.height as f32 },0.3, 6, Color::LIGHTBLUE);
+
+    if let Some((text, size)) = value {
+        let y = (panel.center_y() - size / 2) as f32;
+        draw_centered_text(rd, font, text, center_x, y, size, Color::WHITE);
+    }
+}
+
+// T-Spin やテトリスなどの消し方を Time の下に表示する
+pub fn draw_callouts(rd: &mut RaylibDrawHandle, font: &Font, callouts: &[String], layout: &Layout) {
+    for (index, callout) in callouts.iter().enumerate() {
+        let y = (layout.callout_y + index as i32 * CALLOUT_LINE_HEIGHT) as f32;
+        rd.draw_text_ex(font, callout, Vector2::new(layout.side_panel_x as f32 + 10.0, y), 24.0, 2.0, Color::YELLOW);
+    }
+}
+
+// 盤面の中央に見出しと各行を重ねて表示する (ゲームオーバー・結果・カウントダウン)
+pub fn draw_board_message(rd: &mut RaylibDrawHandle, font: &Font, layout: &Layout, title: &str, title_color: Color, lines: &[String]) {
+    let board_x = layout.board_x as f32;
+    let board_width = layout.board_width as f32;
+    let height = 60 + lines.len() as i32 * 28 + 10;
+    let top = layout.board_y + layout.board_height / 2 - height / 2;
+
+    rd.draw_rectangle(layout.board_x, top, layout.board_width, height, Color::BLACK.fade(0.7));
+    draw_centered_text(rd, font, title, board_x + board_width / 2.0, top as f32 + 12.0, 38, title_color);
+    for (index, line) in lines.iter().enumerate() {
+        let y = top as f32 + 60.0 + index as f32 * 28.0;
+        draw_centered_text(rd, font, line, board_x + board_width / 2.0, y, 24, Color::WHITE);
+    }
+}
+
+// ウィンドウ全体を使う選択画面 (選んでいる項目に印をつけ、下に説明を出す)
+pub fn draw_menu(rd: &mut RaylibDrawHandle, font: &Font, layout: &Layout, title: &str, items: &[String], selected: usize, footer: &[String]) {
+    let width = layout.width as f32;
+    let height = layout.height as f32;
+
+    draw_centered_text(rd, font, title, width / 2.0, 40.0, 38, Color::GOLD);
+    for (index, item) in items.iter().enumerate() {
+        let y = 110.0 + index as f32 * 32.0;
+        let (text, color) = if index == selected {
+            (format!("> {}", item), Color::WHITE)
+        } else {
+            (item.clone(), Color::LIGHTGRAY)
+        };
+        draw_centered_text(rd, font, &text, width / 2.0, y, 30, color);
+    }
+    for (index, line) in footer.iter().enumerate() {
+        let y = height - 40.0 - (footer.len() - 1 - index) as f32 * 30.0;
+        draw_centered_text(rd, font, line, width / 2.0, y, 24, Color::LIGHTGRAY);
+    }
+}
+
+// ゲームオーバーの原因
+pub fn get_top_out_text(top_out: Option<TopOut>) -> &'static str {
+    match top_out {
+        Some(TopOut::BlockOut) => "BLOCK OUT",
+        Some(TopOut::LockOut) => "LOCK OUT",
+        Some(TopOut::PartialLockOut) => "PARTIAL LOCK OUT",
+        Some(TopOut::GarbageOut) => "TOP OUT",
+        None => "",
+    }
+}
+
+// center_x を中心に揃えて描画する
+pub fn draw_centered_text(rd: &mut RaylibDrawHandle, font: &Font, text: &str, center_x: f32, y: f32, size: i32, color: Color) {
+    let text_width = rd.measure_text(text, size) as f32;
+    rd.draw_text_ex(font, text, Vector2::new(center_x - text_width / 2.0, y), size as f32, 2.0, color);
+}
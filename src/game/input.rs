@@ -0,0 +1,176 @@
+use super::{config::HandlingConfig, engine::Action};
+
+// そのフレームで押し続けているキー (フロントエンドがキーボードから作る)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HeldKeys {
+    pub left: bool,
+    pub right: bool,
+    pub soft_drop: bool,
+}
+
+// 左右移動の DAS / ARR とソフトドロップの連続入力を管理する
+// 返す (Action, 回数) のうち u32::MAX は「動けなくなるまで」を意味する
+pub struct AutoRepeat {
+    previous: HeldKeys,
+    direction: Option<Action>,
+    das_elapsed_ms: f64,
+    arr_elapsed_ms: f64,
+    soft_drop_elapsed_ms: f64,
+}
+
+impl AutoRepeat {
+    pub fn new() -> Self {
+        AutoRepeat {
+            previous: HeldKeys::default(),
+            direction: None,
+            das_elapsed_ms: 0.0,
+            arr_elapsed_ms: 0.0,
+            soft_drop_elapsed_ms: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, held: HeldKeys, handling: &HandlingConfig, gravity_ms: f64, delta_ms: f64) -> Vec<(Action, u32)> {
+        let mut actions = Vec::new();
+
+        self.update_shift(held, handling, delta_ms, &mut actions);
+        self.update_soft_drop(held, handling, gravity_ms, delta_ms, &mut actions);
+
+        self.previous = held;
+        actions
+    }
+
+    fn update_shift(&mut self, held: HeldKeys, handling: &HandlingConfig, delta_ms: f64, actions: &mut Vec<(Action, u32)>) {
+        let left_pressed = held.left && !self.previous.left;
+        let right_pressed = held.right && !self.previous.right;
+
+        // 押した瞬間に1マス動かし、後から押した方向を優先する
+        if left_pressed || right_pressed {
+            let direction = if right_pressed { Action::MoveRight } else { Action::MoveLeft };
+            self.start_shift(direction);
+            actions.push((direction, 1));
+            return;
+        }
+
+        // 押していた方向を離したら、反対側が押されていればそちらで DAS をやり直す
+        let is_held = |direction: Action| match direction {
+            Action::MoveLeft => held.left,
+            _ => held.right,
+        };
+        match self.direction {
+            Some(direction) if !is_held(direction) => {
+                let other = if direction == Action::MoveLeft { Action::MoveRight } else { Action::MoveLeft };
+                if is_held(other) {
+                    self.start_shift(other);
+                } else {
+                    self.direction = None;
+                }
+                return;
+            }
+            None => return,
+            _ => {}
+        }
+
+        let direction = self.direction.unwrap();
+        let was_charged = self.das_elapsed_ms >= handling.das_ms;
+        self.das_elapsed_ms += delta_ms;
+        if self.das_elapsed_ms < handling.das_ms {
+            return;
+        }
+
+        // ARR 0 は壁まで一気に動かす
+        if handling.arr_ms <= 0.0 {
+            actions.push((direction, u32::MAX));
+            return;
+        }
+
+        // DAS が溜まった瞬間に1回動かし、その後は ARR ごとに動かす
+        if was_charged {
+            self.arr_elapsed_ms += delta_ms;
+        } else {
+            self.arr_elapsed_ms = handling.arr_ms + self.das_elapsed_ms - handling.das_ms;
+        }
+        let count = (self.arr_elapsed_ms / handling.arr_ms).floor();
+        self.arr_elapsed_ms -= count * handling.arr_ms;
+        if count > 0.0 {
+            actions.push((direction, count as u32));
+        }
+    }
+
+    fn start_shift(&mut self, direction: Action) {
+        self.direction = Some(direction);
+        self.das_elapsed_ms = 0.0;
+        self.arr_elapsed_ms = 0.0;
+    }
+
+    // ソフトドロップは重力の soft_drop_factor 倍の速さで落とす
+    fn update_soft_drop(&mut self, held: HeldKeys, handling: &HandlingConfig, gravity_ms: f64, delta_ms: f64, actions: &mut Vec<(Action, u32)>) {
+        if !held.soft_drop {
+            return;
+        }
+
+        let interval_ms = gravity_ms / handling.soft_drop_factor;
+        if interval_ms <= 0.0 {
+            actions.push((Action::SoftDrop, u32::MAX));
+            return;
+        }
+
+        if !self.previous.soft_drop {
+            self.soft_drop_elapsed_ms = interval_ms;
+        } else {
+            self.soft_drop_elapsed_ms += delta_ms;
+        }
+        let count = (self.soft_drop_elapsed_ms / interval_ms).floor();
+        self.soft_drop_elapsed_ms -= count * interval_ms;
+        if count > 0.0 {
+            actions.push((Action::SoftDrop, count as u32));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_handling(das_ms: f64, arr_ms: f64) -> HandlingConfig {
+        HandlingConfig { das_ms, arr_ms, soft_drop_factor: 20.0 }
+    }
+
+    const RIGHT: HeldKeys = HeldKeys { left: false, right: true, soft_drop: false };
+
+    #[test]
+    fn shifts_after_das_then_every_arr() {
+        let handling = create_handling(100.0, 20.0);
+        let mut auto_repeat = AutoRepeat::new();
+
+        // 押した瞬間に1マス、DAS が溜まるまでは動かない
+        assert_eq!(auto_repeat.update(RIGHT, &handling, 1000.0, 0.0), vec![(Action::MoveRight, 1)]);
+        assert!(auto_repeat.update(RIGHT, &handling, 1000.0, 99.0).is_empty());
+        assert_eq!(auto_repeat.update(RIGHT, &handling, 1000.0, 1.0), vec![(Action::MoveRight, 1)]);
+        assert!(auto_repeat.update(RIGHT, &handling, 1000.0, 19.0).is_empty());
+        assert_eq!(auto_repeat.update(RIGHT, &handling, 1000.0, 21.0), vec![(Action::MoveRight, 2)]);
+    }
+
+    #[test]
+    fn zero_arr_moves_to_wall() {
+        let handling = create_handling(100.0, 0.0);
+        let mut auto_repeat = AutoRepeat::new();
+
+        auto_repeat.update(RIGHT, &handling, 1000.0, 0.0);
+        assert_eq!(auto_repeat.update(RIGHT, &handling, 1000.0, 100.0), vec![(Action::MoveRight, u32::MAX)]);
+    }
+
+    #[test]
+    fn last_pressed_direction_wins() {
+        let handling = create_handling(100.0, 20.0);
+        let mut auto_repeat = AutoRepeat::new();
+        let both = HeldKeys { left: true, ..RIGHT };
+
+        auto_repeat.update(RIGHT, &handling, 1000.0, 0.0);
+        assert_eq!(auto_repeat.update(both, &handling, 1000.0, 50.0), vec![(Action::MoveLeft, 1)]);
+
+        // 左を離すと右で DAS をやり直す
+        assert!(auto_repeat.update(RIGHT, &handling, 1000.0, 50.0).is_empty());
+        assert!(auto_repeat.update(RIGHT, &handling, 1000.0, 99.0).is_empty());
+        assert_eq!(auto_repeat.update(RIGHT, &handling, 1000.0, 1.0), vec![(Action::MoveRight, 1)]);
+    }
+}
@@ -0,0 +1,160 @@
+use super::config::GameConfig;
+
+// 盤面の周りの余白と、右側に並べる HUD の列の幅・間隔
+const MARGIN: i32 = 11;
+const PANEL_GAP: i32 = 9;
+const PANEL_WIDTH: i32 = 170;
+const PANEL_SPACING: i32 = 180;
+
+// cell_size を省略したときに盤面の高さを合わせる目安
+const DEFAULT_BOARD_HEIGHT: i32 = 600;
+const MAX_AUTO_CELL_SIZE: i32 = 30;
+
+// HUD の見出しから枠までの高さ、数値の枠の高さ、枠の下から次の見出しまでの間隔
+const LABEL_HEIGHT: i32 = 40;
+const VALUE_BOX_HEIGHT: i32 = 60;
+const SECTION_GAP: i32 = 10;
+
+// Hold / Next のブロックのマスの大きさ (盤面のマスの 2/3、この範囲に収める)
+const MIN_PREVIEW_CELL_SIZE: i32 = 8;
+const MAX_PREVIEW_CELL_SIZE: i32 = 20;
+
+// T-Spin などの消し方の表示 (行数と1行の高さ)
+const CALLOUT_LINES: i32 = 5;
+pub const CALLOUT_LINE_HEIGHT: i32 = 28;
+
+// 選択画面 (モードの一覧) が収まる高さ
+const MIN_WINDOW_HEIGHT: i32 = 440;
+
+// HUD の1項目 (見出しの y と、その下の枠の y・高さ)
+#[derive(Clone, Copy, Debug)]
+pub struct PanelBox {
+    pub label_y: i32,
+    pub y: i32,
+    pub height: i32,
+}
+
+impl PanelBox {
+    fn new(label_y: i32, height: i32) -> Self {
+        PanelBox {
+            label_y,
+            y: label_y + LABEL_HEIGHT,
+            height,
+        }
+    }
+
+    // この枠の下に並べる項目
+    fn below(&self, height: i32) -> Self {
+        PanelBox::new(self.bottom() + SECTION_GAP, height)
+    }
+
+    pub fn bottom(&self) -> i32 {
+        self.y + self.height
+    }
+
+    pub fn center_y(&self) -> i32 {
+        self.y + self.height / 2
+    }
+}
+
+// 盤面の大きさと Next の数から決まるウィンドウと HUD の配置
+#[derive(Clone, Copy, Debug)]
+pub struct Layout {
+    pub cell_size: i32,
+    pub board_x: i32,
+    pub board_y: i32,
+    pub board_width: i32,
+    pub board_height: i32,
+    // Score / Hold / Next の列と、Level / Lines / Time の列の左端
+    pub panel_x: i32,
+    pub side_panel_x: i32,
+    pub panel_width: i32,
+    pub score: PanelBox,
+    pub hold: PanelBox,
+    // Next の枠 (高さは一番多く表示するときの分)
+    pub next: PanelBox,
+    pub level: PanelBox,
+    pub lines: PanelBox,
+    pub time: PanelBox,
+    pub callout_y: i32,
+    // Hold / Next のマスの大きさと、Next の1個分の高さ
+    pub preview_cell_size: i32,
+    pub preview_slot_height: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Layout {
+    pub fn new(config: &GameConfig) -> Self {
+        let board = &config.board;
+        let cell_size = board
+            .cell_size
+            .unwrap_or_else(|| (DEFAULT_BOARD_HEIGHT / board.rows as i32).clamp(1, MAX_AUTO_CELL_SIZE));
+
+        let board_width = board.columns as i32 * cell_size;
+        let board_height = board.rows as i32 * cell_size;
+        let panel_x = MARGIN + board_width + PANEL_GAP;
+        let side_panel_x = panel_x + PANEL_SPACING;
+
+        // プレビューは縦2マス分 (横向きの I) と余白が1個分、Hold は縦3マス分まで入る
+        let preview_cell_size = (cell_size * 2 / 3).clamp(MIN_PREVIEW_CELL_SIZE, MAX_PREVIEW_CELL_SIZE);
+        let preview_slot_height = preview_cell_size * 2 + SECTION_GAP;
+        let hold_height = preview_cell_size * 3 + 2 * SECTION_GAP;
+
+        // HUD の見出しは盤面の上端に揃える
+        let top = MARGIN + 4;
+        let score = PanelBox::new(top, VALUE_BOX_HEIGHT);
+        let hold = score.below(hold_height);
+        let next = hold.below(Layout::get_next_height(config.max_next_count(), preview_slot_height));
+        let level = PanelBox::new(top, VALUE_BOX_HEIGHT);
+        let lines = level.below(VALUE_BOX_HEIGHT);
+        let time = lines.below(VALUE_BOX_HEIGHT);
+        let callout_y = time.bottom() + 15;
+
+        let hud_bottom = next.bottom().max(callout_y + CALLOUT_LINES * CALLOUT_LINE_HEIGHT);
+        let height = (MARGIN + board_height + PANEL_GAP)
+            .max(hud_bottom + MARGIN)
+            .max(MIN_WINDOW_HEIGHT);
+
+        Layout {
+            cell_size,
+            board_x: MARGIN,
+            board_y: MARGIN,
+            board_width,
+            board_height,
+            panel_x,
+            side_panel_x,
+            panel_width: PANEL_WIDTH,
+            score,
+            hold,
+            next,
+            level,
+            lines,
+            time,
+            callout_y,
+            preview_cell_size,
+            preview_slot_height,
+            width: side_panel_x + PANEL_SPACING,
+            height,
+        }
+    }
+
+    fn get_next_height(count: usize, slot_height: i32) -> i32 {
+        count as i32 * slot_height + SECTION_GAP
+    }
+
+    // count 個表示するときの Next の枠の高さ
+    pub fn next_height(&self, count: usize) -> i32 {
+        Layout::get_next_height(count, self.preview_slot_height)
+    }
+
+    // Next の index 番目のブロックの中心の y 座標
+    pub fn next_slot_center_y(&self, index: usize) -> i32 {
+        self.next.y + SECTION_GAP / 2 + self.preview_slot_height / 2 + index as i32 * self.preview_slot_height
+    }
+
+    // HUD の列の中央の x 座標
+    pub fn panel_center_x(&self) -> i32 {
+        self.panel_x + self.panel_width / 2
+    }
+}
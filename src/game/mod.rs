@@ -1 +1,12 @@
-pub mod game;
\ No newline at end of file
+pub mod config;
+pub mod engine;
+#[allow(clippy::module_inception)]
+pub mod game;
+pub mod gravity;
+pub mod hud;
+pub mod input;
+pub mod layout;
+pub mod mode;
+pub mod puzzle;
+pub mod scoring;
+pub mod state;
\ No newline at end of file
@@ -0,0 +1,251 @@
+use super::{config::GameConfig, puzzle::PuzzleGoal, scoring::LineClear};
+
+// 設定やコマンドラインで指定できるモード名
+// endless: 終わりなし、sprint: 目標ライン数までのタイム、ultra: 制限時間内のスコア、
+// marathon: 150 ライン (またはレベル 15 をクリア) まで、dig: 最初のゴミ行をすべて消すまでのタイム、
+// survival: 一定時間ごとにせり上がるゴミ行に耐える、puzzle: パズル集から選んで目標を達成する
+pub const MODE_NAMES: [&str; 7] = ["endless", "sprint", "ultra", "marathon", "dig", "survival", "puzzle"];
+
+// Sprint で選べる目標ライン数
+pub const SPRINT_GOALS: [u32; 3] = [20, 40, 100];
+
+pub const ULTRA_TIME_LIMIT_MS: f64 = 120_000.0;
+pub const MARATHON_LINE_GOAL: u32 = 150;
+pub const MARATHON_LEVEL_GOAL: u32 = 15;
+
+// Survival のせり上がり間隔 (SPEEDUP_MS ごとに STEP_MS ずつ短くなる)
+pub const SURVIVAL_START_INTERVAL_MS: f64 = 8000.0;
+pub const SURVIVAL_MIN_INTERVAL_MS: f64 = 1500.0;
+pub const SURVIVAL_INTERVAL_STEP_MS: f64 = 500.0;
+pub const SURVIVAL_SPEEDUP_MS: f64 = 20_000.0;
+
+// モードごとの終了条件
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameMode {
+    Endless,
+    Sprint { line_goal: u32 },
+    Ultra { time_limit_ms: f64 },
+    Marathon { line_goal: u32, level_goal: u32 },
+    Dig { garbage_rows: u32 },
+    Survival,
+    Puzzle { goal: PuzzleGoal },
+}
+
+// 終了時の記録
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GameRecord {
+    pub time_ms: f64,
+    pub pieces: u32,
+    pub lines: u32,
+    pub level: u32,
+    pub score: u32,
+    pub garbage_cleared: u32,
+    // 目標を達成した (Puzzle でブロックを使い切って終わったときは false)
+    pub cleared: bool,
+}
+
+impl GameRecord {
+    // 1秒あたりに置いたブロック数 (Pieces Per Second)
+    pub fn pps(&self) -> f64 {
+        if self.time_ms <= 0.0 {
+            return 0.0;
+        }
+        self.pieces as f64 / (self.time_ms / 1000.0)
+    }
+}
+
+impl GameMode {
+    // Puzzle はパズルを選んだときに Engine::start_puzzle で目標ごと決まる
+    pub fn from_config(config: &GameConfig) -> Self {
+        match config.mode.as_str() {
+            "sprint" => GameMode::Sprint { line_goal: config.sprint_goal },
+            "ultra" => GameMode::Ultra { time_limit_ms: ULTRA_TIME_LIMIT_MS },
+            "marathon" => GameMode::Marathon { line_goal: MARATHON_LINE_GOAL, level_goal: MARATHON_LEVEL_GOAL },
+            "dig" => GameMode::Dig { garbage_rows: config.dig_rows as u32 },
+            "survival" => GameMode::Survival,
+            _ => GameMode::Endless,
+        }
+    }
+
+    // ブロックを固定したあとの終了判定 (clear はその固定での消し方)
+    pub fn is_goal_reached(&self, record: &GameRecord, clear: Option<&LineClear>) -> bool {
+        match *self {
+            GameMode::Puzzle { goal } => goal.is_met(record.lines, clear),
+            GameMode::Sprint { line_goal } => record.lines >= line_goal,
+            // レベル 15 の途中ではなく、15 を終えて次のレベルに上がったところで終わる
+            GameMode::Marathon { line_goal, level_goal } => {
+                clear.is_some_and(|clear| clear.lines > 0) && (record.lines >= line_goal || record.level > level_goal)
+            }
+            GameMode::Dig { garbage_rows } => record.garbage_cleared >= garbage_rows,
+            GameMode::Endless | GameMode::Ultra { .. } | GameMode::Survival => false,
+        }
+    }
+
+    pub fn time_limit_ms(&self) -> Option<f64> {
+        match *self {
+            GameMode::Ultra { time_limit_ms } => Some(time_limit_ms),
+            _ => None,
+        }
+    }
+
+    // ゴミ行がせり上がる間隔 (経過時間とともに短くなる)
+    pub fn garbage_interval_ms(&self, elapsed_ms: f64) -> Option<f64> {
+        match self {
+            GameMode::Survival => {
+                let steps = (elapsed_ms / SURVIVAL_SPEEDUP_MS).floor();
+                Some((SURVIVAL_START_INTERVAL_MS - steps * SURVIVAL_INTERVAL_STEP_MS).max(SURVIVAL_MIN_INTERVAL_MS))
+            }
+            _ => None,
+        }
+    }
+
+    // 開始時に積んでおくゴミ行の数
+    pub fn starting_garbage(&self) -> u32 {
+        match *self {
+            GameMode::Dig { garbage_rows } => garbage_rows,
+            _ => 0,
+        }
+    }
+
+    // Lines の欄の表示 (目標があれば "12/40"、Dig は消したゴミ行の数)
+    pub fn get_lines_text(&self, record: &GameRecord) -> String {
+        match *self {
+            GameMode::Sprint { line_goal } | GameMode::Marathon { line_goal, .. } => format!("{}/{}", record.lines, line_goal),
+            GameMode::Dig { garbage_rows } => format!("{}/{}", record.garbage_cleared, garbage_rows),
+            _ => format!("{}", record.lines),
+        }
+    }
+
+    // Time の欄に表示する時間 (制限時間のあるモードは残り時間)
+    pub fn display_time_ms(&self, elapsed_ms: f64) -> f64 {
+        match self.time_limit_ms() {
+            Some(limit) => (limit - elapsed_ms).max(0.0),
+            None => elapsed_ms,
+        }
+    }
+
+    // 結果画面の見出しと各行 (Sprint はタイム、Ultra はスコアが順位の基準)
+    // 目標に届かずトップアウトしたときも同じ項目を出す
+    pub fn get_results(&self, record: &GameRecord) -> (&'static str, Vec<String>) {
+        let (title, lines) = self.get_result_lines(record);
+        match self {
+            GameMode::Puzzle { .. } if !record.cleared => ("FAILED", lines),
+            _ if !record.cleared => ("RESULTS", lines),
+            _ => (title, lines),
+        }
+    }
+
+    fn get_result_lines(&self, record: &GameRecord) -> (&'static str, Vec<String>) {
+        match self {
+            GameMode::Sprint { .. } | GameMode::Dig { .. } => ("FINISHED", vec![
+                format_time(record.time_ms),
+                format!("{} pieces", record.pieces),
+                format!("{:.2} PPS", record.pps()),
+            ]),
+            GameMode::Ultra { .. } => ("TIME UP", vec![
+                format!("Score {}", record.score),
+                format!("{} lines", record.lines),
+                format!("{:.2} PPS", record.pps()),
+            ]),
+            GameMode::Marathon { .. } => ("COMPLETE", vec![
+                format!("Score {}", record.score),
+                format!("{} lines / Lv {}", record.lines, record.level),
+                format_time(record.time_ms),
+            ]),
+            GameMode::Puzzle { goal } => ("SOLVED", vec![
+                goal.describe(),
+                format!("{} pieces", record.pieces),
+                format_time(record.time_ms),
+            ]),
+            GameMode::Endless | GameMode::Survival => ("RESULTS", vec![
+                format!("Score {}", record.score),
+                format!("{} lines", record.lines),
+                format_time(record.time_ms),
+            ]),
+        }
+    }
+}
+
+// モード選択画面に出す説明
+pub fn get_mode_description(name: &str, config: &GameConfig) -> String {
+    match name {
+        "sprint" => format!("Clear {} lines as fast as you can", config.sprint_goal),
+        "ultra" => String::from("Score as much as you can in 2 minutes"),
+        "marathon" => format!("Clear {} lines or finish level {}", MARATHON_LINE_GOAL, MARATHON_LEVEL_GOAL),
+        "dig" => format!("Dig through {} garbage rows", config.dig_rows),
+        "survival" => String::from("Hold out against rising garbage"),
+        "puzzle" => String::from("Solve puzzles with a fixed queue"),
+        _ => String::from("Play until you top out"),
+    }
+}
+
+// ms を "1:23.456" の形式にする
+pub fn format_time(time_ms: f64) -> String {
+    let total_ms = time_ms.max(0.0) as u64;
+    let minutes = total_ms / 60_000;
+    let seconds = total_ms / 1000 % 60;
+    let millis = total_ms % 1000;
+    format!("{}:{:02}.{:03}", minutes, seconds, millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_record(lines: u32, level: u32) -> GameRecord {
+        GameRecord {
+            time_ms: 0.0,
+            pieces: 0,
+            lines,
+            level,
+            score: 0,
+            garbage_cleared: 0,
+            cleared: false,
+        }
+    }
+
+    fn create_clear(lines: u32) -> LineClear {
+        LineClear { lines, ..LineClear::default() }
+    }
+
+    #[test]
+    fn marathon_ends_after_level_goal() {
+        let mode = GameMode::Marathon { line_goal: MARATHON_LINE_GOAL, level_goal: MARATHON_LEVEL_GOAL };
+
+        // 140 ラインでレベル 15 に上がっただけでは終わらない
+        assert!(!mode.is_goal_reached(&create_record(140, 15), Some(&create_clear(1))));
+        assert!(mode.is_goal_reached(&create_record(150, 16), Some(&create_clear(1))));
+    }
+
+    #[test]
+    fn marathon_needs_line_clear() {
+        let mode = GameMode::Marathon { line_goal: MARATHON_LINE_GOAL, level_goal: MARATHON_LEVEL_GOAL };
+
+        // --level 15 で始めても、最初の固定では終わらない
+        assert!(!mode.is_goal_reached(&create_record(0, 15), None));
+        assert!(!mode.is_goal_reached(&create_record(0, 15), Some(&create_clear(0))));
+        assert!(mode.is_goal_reached(&create_record(10, 16), Some(&create_clear(2))));
+    }
+
+    #[test]
+    fn sprint_ends_at_line_goal() {
+        let mut config = GameConfig::new();
+        config.mode = String::from("sprint");
+        config.sprint_goal = 20;
+        let mode = GameMode::from_config(&config);
+
+        assert_eq!(mode, GameMode::Sprint { line_goal: 20 });
+        assert!(!mode.is_goal_reached(&create_record(19, 2), Some(&create_clear(1))));
+        assert!(mode.is_goal_reached(&create_record(21, 3), Some(&create_clear(2))));
+    }
+
+    #[test]
+    fn survival_interval_gets_shorter() {
+        let mode = GameMode::Survival;
+
+        assert_eq!(mode.garbage_interval_ms(0.0), Some(SURVIVAL_START_INTERVAL_MS));
+        assert_eq!(mode.garbage_interval_ms(SURVIVAL_SPEEDUP_MS), Some(SURVIVAL_START_INTERVAL_MS - SURVIVAL_INTERVAL_STEP_MS));
+        assert_eq!(mode.garbage_interval_ms(3_600_000.0), Some(SURVIVAL_MIN_INTERVAL_MS));
+        assert_eq!(GameMode::Endless.garbage_interval_ms(0.0), None);
+    }
+}
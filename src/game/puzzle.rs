@@ -0,0 +1,205 @@
+use std::fs;
+
+use serde::Deserialize;
+
+use crate::{stage::grid::GARBAGE_CELL, tetris::{piece_set::PieceSet, spin::SpinType}};
+
+use super::scoring::LineClear;
+
+// パズル集 (Puzzle モードの選択画面に並ぶ)
+#[derive(Clone, Debug, Deserialize)]
+pub struct PuzzlePack {
+    pub name: String,
+    pub puzzles: Vec<Puzzle>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Puzzle {
+    pub name: String,
+    // 盤面の一番下から詰めて置く行 ('.' は空、'#' はゴミ、ブロック名の1文字はそのブロック)
+    #[serde(default)]
+    pub board: Vec<String>,
+    // 出てくるブロックの名前 (この順番で出て、なくなったら終わり)
+    pub queue: Vec<String>,
+    pub goal: PuzzleGoal,
+    // Next に表示するブロック数 (省略するとモードの設定)
+    #[serde(default)]
+    pub next_count: Option<usize>,
+}
+
+// 例: { type = "lines", count = 4 }, { type = "perfect-clear" }, { type = "t-spin", lines = 2 }
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum PuzzleGoal {
+    Lines { count: u32 },
+    PerfectClear,
+    TSpin { lines: u32 },
+}
+
+impl PuzzlePack {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|err| format!("Failed to read {}: {}", path, err))?;
+        let pack: PuzzlePack = toml::from_str(&text).map_err(|err| format!("Failed to parse {}: {}", path, err))?;
+        if pack.puzzles.is_empty() {
+            return Err(format!("{} has no puzzles", path));
+        }
+        Ok(pack)
+    }
+
+    // このピースセットと盤面で遊べないパズルはメッセージを出して外す
+    pub fn retain_playable(&mut self, piece_set: &PieceSet, columns: usize, rows: usize) {
+        self.puzzles.retain(|puzzle| {
+            let result = puzzle
+                .get_rows(piece_set, columns, rows)
+                .and_then(|_| puzzle.get_queue(piece_set));
+            match result {
+                Ok(_) => true,
+                Err(err) => {
+                    eprintln!("Skipping puzzle {}: {}", puzzle.name, err);
+                    false
+                }
+            }
+        });
+    }
+}
+
+impl Puzzle {
+    // 盤面の行をマスの値にする (上から順、最後の行が盤面の一番下)
+    pub fn get_rows(&self, piece_set: &PieceSet, columns: usize, rows: usize) -> Result<Vec<Vec<i32>>, String> {
+        if self.board.len() > rows {
+            return Err(format!("board has {} rows but only {} are visible", self.board.len(), rows));
+        }
+
+        self.board
+            .iter()
+            .map(|line| {
+                if line.chars().count() != columns {
+                    return Err(format!("board row \"{}\" is not {} columns wide", line, columns));
+                }
+                line.chars()
+                    .map(|cell| match cell {
+                        '.' => Ok(0),
+                        '#' => Ok(GARBAGE_CELL),
+                        _ => get_piece_index(piece_set, &cell.to_string()).map(|index| index as i32 + 1),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // 出てくるブロックのランダマイザの番号
+    pub fn get_queue(&self, piece_set: &PieceSet) -> Result<Vec<usize>, String> {
+        if self.queue.is_empty() {
+            return Err(String::from("queue is empty"));
+        }
+        self.queue.iter().map(|name| get_piece_index(piece_set, name)).collect()
+    }
+}
+
+impl PuzzleGoal {
+    // ブロックを固定するたびに判定する
+    pub fn is_met(&self, lines: u32, clear: Option<&LineClear>) -> bool {
+        match *self {
+            PuzzleGoal::Lines { count } => lines >= count,
+            PuzzleGoal::PerfectClear => clear.is_some_and(|clear| clear.perfect_clear),
+            PuzzleGoal::TSpin { lines } => clear.is_some_and(|clear| clear.spin == SpinType::Full && clear.lines == lines),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match *self {
+            PuzzleGoal::Lines { count: 1 } => String::from("Clear 1 line"),
+            PuzzleGoal::Lines { count } => format!("Clear {} lines", count),
+            PuzzleGoal::PerfectClear => String::from("Perfect clear"),
+            PuzzleGoal::TSpin { lines } => {
+                let name = match lines {
+                    0 => "",
+                    1 => " Single",
+                    2 => " Double",
+                    _ => " Triple",
+                };
+                format!("T-Spin{}", name)
+            }
+        }
+    }
+}
+
+fn get_piece_index(piece_set: &PieceSet, name: &str) -> Result<usize, String> {
+    piece_set
+        .names()
+        .iter()
+        .position(|&piece| piece == name)
+        .ok_or_else(|| format!("unknown piece {}", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_puzzle(board: &[&str], queue: &[&str]) -> Puzzle {
+        Puzzle {
+            name: String::from("Test"),
+            board: board.iter().map(|line| line.to_string()).collect(),
+            queue: queue.iter().map(|name| name.to_string()).collect(),
+            goal: PuzzleGoal::Lines { count: 1 },
+            next_count: None,
+        }
+    }
+
+    #[test]
+    fn reads_board_rows() {
+        let piece_set = PieceSet::standard();
+        let puzzle = create_puzzle(&["T..#", "####"], &["I"]);
+        let t = piece_set.names().iter().position(|&name| name == "T").unwrap() as i32 + 1;
+
+        let rows = puzzle.get_rows(&piece_set, 4, 20).unwrap();
+        assert_eq!(rows, vec![vec![t, 0, 0, GARBAGE_CELL], vec![GARBAGE_CELL; 4]]);
+    }
+
+    #[test]
+    fn rejects_invalid_board() {
+        let piece_set = PieceSet::standard();
+
+        assert!(create_puzzle(&["...."], &["I"]).get_rows(&piece_set, 5, 20).is_err());
+        assert!(create_puzzle(&["....", "...."], &["I"]).get_rows(&piece_set, 4, 1).is_err());
+        assert!(create_puzzle(&["..X."], &["I"]).get_rows(&piece_set, 4, 20).is_err());
+    }
+
+    #[test]
+    fn reads_queue() {
+        let piece_set = PieceSet::standard();
+        let names = piece_set.names();
+        let index = |name: &str| names.iter().position(|&piece| piece == name).unwrap();
+
+        assert_eq!(create_puzzle(&[], &["T", "I"]).get_queue(&piece_set), Ok(vec![index("T"), index("I")]));
+        assert!(create_puzzle(&[], &[]).get_queue(&piece_set).is_err());
+        assert!(create_puzzle(&[], &["X"]).get_queue(&piece_set).is_err());
+    }
+
+    #[test]
+    fn checks_goals() {
+        let clear = |lines: u32, spin: SpinType, perfect_clear: bool| LineClear { lines, spin, perfect_clear, ..LineClear::default() };
+
+        assert!(!PuzzleGoal::Lines { count: 4 }.is_met(3, Some(&clear(3, SpinType::None, false))));
+        assert!(PuzzleGoal::Lines { count: 4 }.is_met(4, Some(&clear(1, SpinType::None, false))));
+
+        assert!(!PuzzleGoal::PerfectClear.is_met(2, Some(&clear(2, SpinType::None, false))));
+        assert!(PuzzleGoal::PerfectClear.is_met(2, Some(&clear(2, SpinType::None, true))));
+        assert!(!PuzzleGoal::PerfectClear.is_met(0, None));
+
+        // Mini や消したライン数が違う T-Spin は数えない
+        let goal = PuzzleGoal::TSpin { lines: 2 };
+        assert!(goal.is_met(2, Some(&clear(2, SpinType::Full, false))));
+        assert!(!goal.is_met(2, Some(&clear(2, SpinType::Mini, false))));
+        assert!(!goal.is_met(1, Some(&clear(1, SpinType::Full, false))));
+    }
+
+    #[test]
+    fn bundled_pack_is_playable() {
+        let mut pack: PuzzlePack = toml::from_str(include_str!("../../puzzles/basics.toml")).unwrap();
+        let count = pack.puzzles.len();
+
+        pack.retain_playable(&PieceSet::standard(), 10, 20);
+        assert_eq!(pack.puzzles.len(), count);
+    }
+}
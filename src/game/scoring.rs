@@ -0,0 +1,130 @@
+use crate::tetris::spin::SpinType;
+
+// ガイドライン準拠の得点計算 (状態は持たず、Engine から渡された内容だけで計算する)
+
+// ソフトドロップ・ハードドロップで1段あたりに入る点数
+pub const SOFT_DROP_POINTS: u32 = 1;
+pub const HARD_DROP_POINTS: u32 = 2;
+
+// 1回の固定で起きたライン消去の内容
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LineClear {
+    pub lines: u32,
+    pub spin: SpinType,
+    // 連続でラインを消した回数 (1回目は 0)
+    pub combo: u32,
+    // 直前の「難しい消し方」から途切れずに続いているか
+    pub back_to_back: bool,
+    pub perfect_clear: bool,
+}
+
+impl LineClear {
+    // Back-to-Back の対象になる消し方 (テトリスか、ラインを消した T-Spin)
+    pub fn is_difficult(&self) -> bool {
+        self.lines >= 4 || (self.lines > 0 && self.spin != SpinType::None)
+    }
+}
+
+// 固定1回分の点数 (レベル倍率込み)
+pub fn line_clear_points(clear: &LineClear, level: u32) -> u32 {
+    let level = level.max(1);
+
+    let mut points = action_points(clear.lines, clear.spin);
+    if clear.back_to_back && clear.is_difficult() {
+        points = points * 3 / 2;
+    }
+    if clear.lines > 0 {
+        points += 50 * clear.combo;
+    }
+    if clear.perfect_clear {
+        points += perfect_clear_points(clear.lines, clear.back_to_back);
+    }
+    points * level
+}
+
+pub fn drop_points(rows: u32, is_hard_drop: bool) -> u32 {
+    let points = if is_hard_drop { HARD_DROP_POINTS } else { SOFT_DROP_POINTS };
+    rows * points
+}
+
+fn action_points(lines: u32, spin: SpinType) -> u32 {
+    match (spin, lines) {
+        (SpinType::None, 0) => 0,
+        (SpinType::None, 1) => 100,
+        (SpinType::None, 2) => 300,
+        (SpinType::None, 3) => 500,
+        (SpinType::None, _) => 800,
+        (SpinType::Mini, 0) => 100,
+        (SpinType::Mini, 1) => 200,
+        (SpinType::Mini, _) => 400,
+        (SpinType::Full, 0) => 400,
+        (SpinType::Full, 1) => 800,
+        (SpinType::Full, 2) => 1200,
+        (SpinType::Full, _) => 1600,
+    }
+}
+
+fn perfect_clear_points(lines: u32, back_to_back: bool) -> u32 {
+    match lines {
+        1 => 800,
+        2 => 1200,
+        3 => 1800,
+        _ if back_to_back => 3200,
+        _ => 2000,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clear(lines: u32, spin: SpinType) -> LineClear {
+        LineClear { lines, spin, ..LineClear::default() }
+    }
+
+    #[test]
+    fn line_clear_points_table() {
+        let table = [
+            (SpinType::None, 0, 0),
+            (SpinType::None, 1, 100),
+            (SpinType::None, 2, 300),
+            (SpinType::None, 3, 500),
+            (SpinType::None, 4, 800),
+            (SpinType::Mini, 0, 100),
+            (SpinType::Mini, 1, 200),
+            (SpinType::Mini, 2, 400),
+            (SpinType::Full, 0, 400),
+            (SpinType::Full, 1, 800),
+            (SpinType::Full, 2, 1200),
+            (SpinType::Full, 3, 1600),
+        ];
+        for (spin, lines, points) in table {
+            assert_eq!(line_clear_points(&clear(lines, spin), 1), points, "{:?} {} lines", spin, lines);
+        }
+    }
+
+    #[test]
+    fn line_clear_points_bonuses() {
+        // レベル倍率 (0 は 1 として扱う)
+        assert_eq!(line_clear_points(&clear(4, SpinType::None), 3), 2400);
+        assert_eq!(line_clear_points(&clear(1, SpinType::None), 0), 100);
+
+        // Back-to-Back は難しい消し方だけ 1.5 倍
+        let tetris = LineClear { back_to_back: true, ..clear(4, SpinType::None) };
+        assert_eq!(line_clear_points(&tetris, 1), 1200);
+        let single = LineClear { back_to_back: true, ..clear(1, SpinType::None) };
+        assert_eq!(line_clear_points(&single, 1), 100);
+
+        // コンボはラインを消したときだけ加算する
+        let combo = LineClear { combo: 2, ..clear(1, SpinType::None) };
+        assert_eq!(line_clear_points(&combo, 2), 400);
+        let no_lines = LineClear { combo: 2, ..clear(0, SpinType::None) };
+        assert_eq!(line_clear_points(&no_lines, 1), 0);
+
+        // パーフェクトクリア
+        let perfect = LineClear { perfect_clear: true, ..clear(4, SpinType::None) };
+        assert_eq!(line_clear_points(&perfect, 1), 2800);
+        let perfect_b2b = LineClear { perfect_clear: true, back_to_back: true, ..clear(4, SpinType::None) };
+        assert_eq!(line_clear_points(&perfect_b2b, 1), 4400);
+    }
+}
@@ -0,0 +1,31 @@
+// 画面の状態 (入力と描画は Game が状態ごとに切り替える)
+//
+// Title -> ModeSelect -> (PuzzleSelect) -> Countdown -> Playing -> GameOver -> Results
+//                                              ^          |                     |
+//                                              |          v                     v
+//                                  (再開) Countdown <- Paused <-> Settings   Countdown (やり直し)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameState {
+    Title,
+    ModeSelect { selected: usize },
+    PuzzleSelect { selected: usize },
+    // 開始前の 3-2-1
+    Countdown { remaining_ms: f64 },
+    Playing,
+    // ポーズメニュー (盤面は隠す)
+    Paused { selected: usize },
+    // ポーズメニューから開く操作の設定
+    Settings { selected: usize },
+    // トップアウトした直後 (Enter で結果へ進む)
+    GameOver,
+    // モードの目標を達成した、または GameOver から進んだ結果画面
+    Results,
+}
+
+pub const COUNTDOWN_MS: f64 = 3000.0;
+
+impl GameState {
+    pub fn countdown() -> Self {
+        GameState::Countdown { remaining_ms: COUNTDOWN_MS }
+    }
+}
@@ -68,8 +68,8 @@ impl SplitMixXoshiro256Rng {
     pub fn new(seed: u64) -> Self {
         let mut state = [0; 4];
         let mut sm64 = SplitMix64::new(seed);
-        for i in 0..4 {
-            state[i] = sm64.next();
+        for value in state.iter_mut() {
+            *value = sm64.next();
         }
         SplitMixXoshiro256Rng { state }
     }
@@ -90,7 +90,7 @@ impl SplitMixXoshiro256Rng {
     }
 
     fn rotl(&self, x: u64, k: u32) -> u64 {
-        (x << k) | (x >> (64 - k))
+        x.rotate_left(k)
     }
 }
 
@@ -0,0 +1,61 @@
+use rand::Rng;
+
+use crate::rand::SplitMixXoshiro256Rng;
+
+// ゴミ行の穴の位置を決める
+// messiness は行ごとに穴の列が変わる確率 (0.0 なら縦一列、1.0 なら毎行ばらばら)
+pub struct GarbageGenerator {
+    rng: SplitMixXoshiro256Rng,
+    messiness: f64,
+    columns: usize,
+    hole_column: Option<usize>,
+}
+
+impl GarbageGenerator {
+    pub fn new(seed: u64, columns: usize, messiness: f64) -> Self {
+        GarbageGenerator {
+            rng: SplitMixXoshiro256Rng::new(seed),
+            messiness,
+            columns,
+            hole_column: None,
+        }
+    }
+
+    // 次のゴミ行の穴の列
+    pub fn next_hole(&mut self) -> usize {
+        let hole_column = match self.hole_column {
+            Some(column) if !self.rng.gen_bool(self.messiness) => column,
+            // 穴を変えるときは前と違う列にする
+            Some(column) if self.columns > 1 => {
+                let offset = self.rng.gen_range(1..self.columns);
+                (column + offset) % self.columns
+            }
+            _ => self.rng.gen_range(0..self.columns),
+        };
+        self.hole_column = Some(hole_column);
+        hole_column
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_holes(messiness: f64) -> Vec<usize> {
+        let mut garbage = GarbageGenerator::new(42, 10, messiness);
+        (0..20).map(|_| garbage.next_hole()).collect()
+    }
+
+    #[test]
+    fn clean_garbage_keeps_hole_column() {
+        let holes = get_holes(0.0);
+        assert!(holes.iter().all(|&column| column == holes[0]));
+    }
+
+    #[test]
+    fn messy_garbage_moves_hole_every_row() {
+        let holes = get_holes(1.0);
+        assert!(holes.windows(2).all(|pair| pair[0] != pair[1]));
+        assert!(holes.iter().all(|&column| column < 10));
+    }
+}
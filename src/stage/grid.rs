@@ -1,23 +1,22 @@
-use raylib::prelude::*;
-
-use crate::utils::colors::get_cell_colors;
+// ゴミ行のマス (ブロックの id 1〜 とは別の値)
+pub const GARBAGE_CELL: i32 = -1;
 
+// 上の hidden_rows 行は見えないバッファで、ブロックはそこにも置ける
 pub struct Grid {
     pub num_rows: usize,
     pub num_cols: usize,
-    pub cell_size: i32,
-    pub grid: Vec<Vec<i32>>,
-    pub colors: Vec<Color>
+    pub hidden_rows: usize,
+    pub grid: Vec<Vec<i32>>
 }
 
 impl Grid {
-    pub fn new() -> Self {
+    pub fn new(visible_rows: usize, columns: usize, hidden_rows: usize) -> Self {
+        let num_rows = visible_rows + hidden_rows;
         let mut grid = Self {
-            num_rows: 20,
-            num_cols: 10,
-            cell_size: 30,
-            grid: vec![vec![0; 10]; 20],
-            colors: get_cell_colors()
+            num_rows,
+            num_cols: columns,
+            hidden_rows,
+            grid: vec![vec![0; columns]; num_rows]
         };
         grid.init();
         grid
@@ -31,18 +30,6 @@ impl Grid {
         }
     }
 
-    pub fn draw(&self, d: &mut RaylibDrawHandle) {
-        for row in 0..self.num_rows {
-            for col in 0..self.num_cols {
-                let cell_value = self.grid[row][col];
-                let x = col as i32 * self.cell_size + 11;
-                let y = row as i32 * self.cell_size + 11;
-
-                d.draw_rectangle(x, y, self.cell_size - 1, self.cell_size - 1, self.colors[cell_value as usize]);
-            }
-        }
-    }
-
     pub fn is_cell_outside(&self, row: usize, col: usize) -> bool {
         row >= self.num_rows || col >= self.num_cols
     }
@@ -83,6 +70,52 @@ impl Grid {
         }
     }
 
+    pub fn move_row_up(&mut self, row: usize, num_rows: i32) {
+        for col in 0..self.num_cols {
+            let target_row = (row as i32 - num_rows) as usize;
+            self.grid[target_row][col] = self.grid[row][col];
+            self.grid[row][col] = 0;
+        }
+    }
+
+    // 全体を1段上げて、一番下に hole_column だけ空いたゴミ行を入れる
+    // 一番上の行にあったマスが押し出されたら true を返す
+    pub fn add_garbage_row(&mut self, hole_column: usize) -> bool {
+        let overflowed = self.grid[0].iter().any(|&cell| cell != 0);
+
+        for row in 1..self.num_rows {
+            self.move_row_up(row, 1);
+        }
+        let bottom = self.num_rows - 1;
+        for col in 0..self.num_cols {
+            self.grid[bottom][col] = if col == hole_column { 0 } else { GARBAGE_CELL };
+        }
+        overflowed
+    }
+
+    // rows を盤面の一番下に揃えて置く (最後の行が一番下)
+    pub fn fill_bottom_rows(&mut self, rows: &[Vec<i32>]) {
+        let top = self.num_rows - rows.len();
+        for (index, row) in rows.iter().enumerate() {
+            self.grid[top + index].copy_from_slice(row);
+        }
+    }
+
+    pub fn is_garbage_row(&self, row: usize) -> bool {
+        self.grid[row].contains(&GARBAGE_CELL)
+    }
+
+    // 揃っている行のうちゴミ行の数 (clear_full_rows の前に数える)
+    pub fn count_full_garbage_rows(&self) -> u32 {
+        (0..self.num_rows)
+            .filter(|&row| self.is_row_full(row) && self.is_garbage_row(row))
+            .count() as u32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.grid.iter().all(|row| row.iter().all(|&cell| cell == 0))
+    }
+
     pub fn rows(&self) -> usize {
         self.grid.len()
     }
@@ -95,3 +128,38 @@ impl Grid {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_garbage_row_at_bottom() {
+        let mut grid = Grid::new(4, 4, 0);
+        grid.grid[3][1] = 2;
+
+        assert!(!grid.add_garbage_row(2));
+        assert_eq!(grid.grid[3], vec![GARBAGE_CELL, GARBAGE_CELL, 0, GARBAGE_CELL]);
+        assert_eq!(grid.grid[2], vec![0, 2, 0, 0]);
+        assert!(grid.is_garbage_row(3));
+        assert!(!grid.is_garbage_row(2));
+    }
+
+    #[test]
+    fn reports_overflow_from_top_row() {
+        let mut grid = Grid::new(2, 4, 0);
+        grid.grid[0][0] = 1;
+
+        assert!(grid.add_garbage_row(0));
+    }
+
+    #[test]
+    fn counts_full_garbage_rows() {
+        let mut grid = Grid::new(4, 4, 0);
+        grid.add_garbage_row(0);
+        grid.add_garbage_row(0);
+        grid.grid[3][0] = 1;
+
+        assert_eq!(grid.count_full_garbage_rows(), 1);
+    }
+}
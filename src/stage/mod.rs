@@ -1 +1,2 @@
-pub mod grid;
\ No newline at end of file
+pub mod garbage;
+pub mod grid;
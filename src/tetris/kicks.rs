@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+// SRS (Super Rotation System) のウォールキック表
+// 値は (x, y) で、x は右、y は上が正 (ガイドラインの表記そのまま)
+// 回転状態は 0 = 出現時, 1 = R (右回転), 2 = 180度, 3 = L (左回転)
+
+const JLSTZ_KICKS: [[(i32, i32); 5]; 8] = [
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)], // 0 -> R
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],     // R -> 0
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],     // R -> 2
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)], // 2 -> R
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],    // 2 -> L
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],  // L -> 2
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],  // L -> 0
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],    // 0 -> L
+];
+
+const I_KICKS: [[(i32, i32); 5]; 8] = [
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)], // 0 -> R
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)], // R -> 0
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)], // R -> 2
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)], // 2 -> R
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)], // 2 -> L
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)], // L -> 2
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)], // L -> 0
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)], // 0 -> L
+];
+
+// 180度回転用 (SRS には定義がないため、一般的な SRS+ の表を使う)
+const HALF_TURN_KICKS: [[(i32, i32); 6]; 4] = [
+    [(0, 0), (0, 1), (1, 1), (-1, 1), (1, 0), (-1, 0)],   // 0 -> 2
+    [(0, 0), (1, 0), (1, 2), (1, 1), (0, 2), (0, 1)],     // R -> L
+    [(0, 0), (0, -1), (-1, -1), (1, -1), (-1, 0), (1, 0)], // 2 -> 0
+    [(0, 0), (-1, 0), (-1, 2), (-1, 1), (0, 2), (0, 1)],  // L -> R
+];
+
+const NO_KICKS: [(i32, i32); 1] = [(0, 0)];
+
+// 回転前後の状態から、表のどの行を使うかを決める
+fn kick_index(from: usize, to: usize) -> Option<usize> {
+    match (from, to) {
+        (0, 1) => Some(0),
+        (1, 0) => Some(1),
+        (1, 2) => Some(2),
+        (2, 1) => Some(3),
+        (2, 3) => Some(4),
+        (3, 2) => Some(5),
+        (3, 0) => Some(6),
+        (0, 3) => Some(7),
+        _ => None,
+    }
+}
+
+// ピースセットで指定するキック表 ("srs", "srs-i", "none" か自作の表)
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum KickTable {
+    #[default]
+    Srs,
+    SrsI,
+    None,
+    // "0>1" のような 回転前>回転後 をキーにした表
+    Custom(HashMap<String, Vec<(i32, i32)>>),
+}
+
+impl KickTable {
+    // 回転前後の状態から、試す順番にキックのオフセットを返す
+    pub fn get_kicks(&self, from: usize, to: usize) -> &[(i32, i32)] {
+        let table = match self {
+            KickTable::None => return &NO_KICKS,
+            KickTable::Custom(table) => {
+                return table
+                    .get(&format!("{}>{}", from, to))
+                    .map_or(&NO_KICKS[..], Vec::as_slice);
+            }
+            KickTable::Srs => &JLSTZ_KICKS,
+            KickTable::SrsI => &I_KICKS,
+        };
+
+        if from < HALF_TURN_KICKS.len() && (from + 2) % 4 == to {
+            return &HALF_TURN_KICKS[from];
+        }
+
+        match kick_index(from, to) {
+            Some(index) => &table[index],
+            None => &NO_KICKS,
+        }
+    }
+}
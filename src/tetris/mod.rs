@@ -1,3 +1,7 @@
 pub mod position;
 pub mod tetris_block;
-pub mod blocks;
\ No newline at end of file
+pub mod kicks;
+pub mod piece_set;
+pub mod polyomino;
+pub mod randomizer;
+pub mod spin;
\ No newline at end of file
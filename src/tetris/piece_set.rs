@@ -0,0 +1,161 @@
+use std::fs;
+
+use serde::Deserialize;
+
+use super::{kicks::KickTable, polyomino::{generate_polyominoes, get_rotations}, position::Position, tetris_block::TetrisBlock};
+
+// 組み込みの標準セット (自作のセットも同じ形式のファイルで読み込める)
+const STANDARD_PIECE_SET: &str = include_str!("../../pieces/standard.toml");
+
+// ブロックの種類の一覧 (並び順がランダマイザの番号、番号 + 1 がブロックID)
+#[derive(Clone, Debug, Deserialize)]
+pub struct PieceSet {
+    pub name: String,
+    pub pieces: Vec<PieceDefinition>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PieceDefinition {
+    pub name: String,
+    pub color: [u8; 3],
+    // 回転状態ごとの形 ('#' がブロック)。順番は 0, R, 2, L
+    pub rotations: Vec<Vec<String>>,
+    // 出現位置の微調整 [row, column]
+    #[serde(default)]
+    pub spawn_offset: [i32; 2],
+    #[serde(default)]
+    pub kicks: KickTable,
+    #[serde(default)]
+    pub t_spin: bool,
+}
+
+impl PieceSet {
+    pub fn standard() -> Self {
+        PieceSet::parse(STANDARD_PIECE_SET).expect("Built-in piece set is valid")
+    }
+
+    // order マスのポリオミノをすべて使うセット (回転状態は自動で作り、色は色相をずらして割り当てる)
+    pub fn polyomino(order: usize) -> Self {
+        let shapes = generate_polyominoes(order);
+        let count = shapes.len();
+
+        let pieces = shapes
+            .iter()
+            .enumerate()
+            .map(|(index, cells)| PieceDefinition {
+                name: format!("{}-{}", order, index + 1),
+                color: get_hue_color(index as f32 / count as f32),
+                rotations: get_rotations(cells).iter().map(|rotation| to_art(rotation)).collect(),
+                spawn_offset: [0, 0],
+                kicks: KickTable::Srs,
+                t_spin: false,
+            })
+            .collect();
+
+        PieceSet {
+            name: format!("{}-omino", order),
+            pieces,
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|err| format!("Failed to read {}: {}", path, err))?;
+        PieceSet::parse(&text).map_err(|err| format!("Failed to parse {}: {}", path, err))
+    }
+
+    fn parse(text: &str) -> Result<Self, String> {
+        let piece_set: PieceSet = toml::from_str(text).map_err(|err| err.to_string())?;
+        piece_set.validate()?;
+        Ok(piece_set)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.pieces.is_empty() {
+            return Err(String::from("piece set has no pieces"));
+        }
+
+        for piece in &self.pieces {
+            if piece.rotations.is_empty() {
+                return Err(format!("piece {} has no rotations", piece.name));
+            }
+            if piece.rotations.iter().any(|rotation| get_cells(rotation).is_empty()) {
+                return Err(format!("piece {} has an empty rotation", piece.name));
+            }
+        }
+        Ok(())
+    }
+
+    // 出現時の向き・位置のテンプレートを作る
+    pub fn to_blocks(&self) -> Vec<TetrisBlock> {
+        self.pieces
+            .iter()
+            .enumerate()
+            .map(|(index, piece)| {
+                let mut block = TetrisBlock::new();
+                block.id = index + 1;
+                block.cells = piece.rotations.iter().map(|rotation| get_cells(rotation)).collect();
+                block.move_block(piece.spawn_offset[0], piece.spawn_offset[1]);
+                block
+            })
+            .collect()
+    }
+
+    pub fn get(&self, id: usize) -> &PieceDefinition {
+        &self.pieces[id - 1]
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.pieces.iter().map(|piece| piece.name.as_str()).collect()
+    }
+}
+
+// セルを "#" と "." の行に戻す (get_cells の逆)
+fn to_art(cells: &[Position]) -> Vec<String> {
+    let rows = cells.iter().map(|cell| cell.row).max().unwrap_or(0) + 1;
+    let columns = cells.iter().map(|cell| cell.column).max().unwrap_or(0) + 1;
+
+    (0..rows)
+        .map(|row| {
+            (0..columns)
+                .map(|column| {
+                    let is_filled = cells.iter().any(|cell| cell.row == row && cell.column == column);
+                    if is_filled { '#' } else { '.' }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// 色相 (0.0〜1.0) から鮮やかな色を作る
+fn get_hue_color(hue: f32) -> [u8; 3] {
+    let (saturation, value) = (0.75, 0.9);
+    let sector = hue * 6.0;
+    let fraction = sector - sector.floor();
+    let p = value * (1.0 - saturation);
+    let q = value * (1.0 - saturation * fraction);
+    let t = value * (1.0 - saturation * (1.0 - fraction));
+
+    let (r, g, b) = match sector as u32 % 6 {
+        0 => (value, t, p),
+        1 => (q, value, p),
+        2 => (p, value, t),
+        3 => (p, q, value),
+        4 => (t, p, value),
+        _ => (value, p, q),
+    };
+    [(r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8]
+}
+
+// "#" の位置を (行, 列) のセルに変換する
+fn get_cells(rotation: &[String]) -> Vec<Position> {
+    rotation
+        .iter()
+        .enumerate()
+        .flat_map(|(row, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|(_, cell)| *cell == '#')
+                .map(move |(column, _)| Position { row: row as i32, column: column as i32 })
+        })
+        .collect()
+}
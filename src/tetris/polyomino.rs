@@ -0,0 +1,123 @@
+use std::collections::BTreeSet;
+
+use super::position::Position;
+
+// 形は (行, 列) の並びを左上に寄せて整列したもの
+type Shape = Vec<(i32, i32)>;
+
+// order マスの片面ポリオミノ (回転で重なるものは同じ、裏返しは別) をすべて作る
+// 並び順は毎回同じになるので、シードを使ったランダマイザでも同じ順番で配られる
+pub fn generate_polyominoes(order: usize) -> Vec<Vec<Position>> {
+    let mut shapes: BTreeSet<Shape> = BTreeSet::new();
+    if order > 0 {
+        shapes.insert(vec![(0, 0)]);
+    }
+
+    // 1マスずつ隣にマスを足して大きくしていく
+    for _ in 1..order {
+        let mut grown = BTreeSet::new();
+        for shape in &shapes {
+            for &(row, column) in shape {
+                for (d_row, d_column) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                    let cell = (row + d_row, column + d_column);
+                    if shape.contains(&cell) {
+                        continue;
+                    }
+                    let mut next = shape.clone();
+                    next.push(cell);
+                    grown.insert(canonical(&next));
+                }
+            }
+        }
+        shapes = grown;
+    }
+
+    shapes.iter().map(|shape| to_positions(&spawn_orientation(shape))).collect()
+}
+
+// 出現時の形を右回転させて 0, R, 2, L の4状態を作る
+// 回転の中心は、出現時の形を上下中央に置いた正方形の枠の中心
+pub fn get_rotations(cells: &[Position]) -> Vec<Vec<Position>> {
+    let height = cells.iter().map(|cell| cell.row).max().unwrap_or(0) + 1;
+    let width = cells.iter().map(|cell| cell.column).max().unwrap_or(0) + 1;
+    let size = height.max(width);
+    let row_offset = (size - height) / 2;
+
+    let mut state: Vec<Position> = cells
+        .iter()
+        .map(|cell| Position { row: cell.row + row_offset, column: cell.column })
+        .collect();
+
+    let mut rotations = Vec::new();
+    for _ in 0..4 {
+        rotations.push(state.clone());
+        state = state
+            .iter()
+            .map(|cell| Position { row: cell.column, column: size - 1 - cell.row })
+            .collect();
+    }
+    rotations
+}
+
+fn normalize(shape: &[(i32, i32)]) -> Shape {
+    let min_row = shape.iter().map(|&(row, _)| row).min().unwrap_or(0);
+    let min_column = shape.iter().map(|&(_, column)| column).min().unwrap_or(0);
+    let mut normalized: Shape = shape.iter().map(|&(row, column)| (row - min_row, column - min_column)).collect();
+    normalized.sort();
+    normalized
+}
+
+fn rotate(shape: &[(i32, i32)]) -> Shape {
+    normalize(&shape.iter().map(|&(row, column)| (column, -row)).collect::<Shape>())
+}
+
+fn get_orientations(shape: &[(i32, i32)]) -> Vec<Shape> {
+    let mut orientations = vec![normalize(shape)];
+    for _ in 1..4 {
+        let next = rotate(orientations.last().unwrap());
+        orientations.push(next);
+    }
+    orientations
+}
+
+// 回転して重なる形の中で最小のものを代表にする
+fn canonical(shape: &[(i32, i32)]) -> Shape {
+    get_orientations(shape).into_iter().min().unwrap()
+}
+
+// 出現時は横長で、一番下の段のマスが多い向きにする (T なら凸が上、L/J なら平らな面が下)
+fn spawn_orientation(shape: &[(i32, i32)]) -> Shape {
+    get_orientations(shape)
+        .into_iter()
+        .min_by_key(|orientation| {
+            let height = orientation.iter().map(|&(row, _)| row).max().unwrap_or(0) + 1;
+            let width = orientation.iter().map(|&(_, column)| column).max().unwrap_or(0) + 1;
+            let bottom = orientation.iter().filter(|&&(row, _)| row == height - 1).count();
+            (height > width, std::cmp::Reverse(bottom), orientation.clone())
+        })
+        .unwrap()
+}
+
+fn to_positions(shape: &[(i32, i32)]) -> Vec<Position> {
+    shape.iter().map(|&(row, column)| Position { row, column }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_one_sided_polyominoes() {
+        let counts: Vec<usize> = (1..=6).map(|order| generate_polyominoes(order).len()).collect();
+        assert_eq!(counts, vec![1, 1, 2, 7, 18, 60]);
+    }
+
+    #[test]
+    fn rotations_keep_every_cell() {
+        for shape in generate_polyominoes(5) {
+            let rotations = get_rotations(&shape);
+            assert_eq!(rotations.len(), 4);
+            assert!(rotations.iter().all(|rotation| rotation.len() == 5));
+        }
+    }
+}
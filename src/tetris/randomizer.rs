@@ -0,0 +1,231 @@
+use std::collections::VecDeque;
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::rand::SplitMixXoshiro256Rng;
+
+// 設定やコマンドラインで指定できるランダマイザ名
+pub const RANDOMIZER_NAMES: [&str; 6] = ["random", "7-bag", "14-bag", "nes", "tgm1", "tgm2"];
+
+// 0..count の一様乱数 (usize の幅に依存せず、どの環境でも同じ列になるよう u32 で引く)
+fn pick(rng: &mut SplitMixXoshiro256Rng, count: usize) -> usize {
+    rng.gen_range(0..count as u32) as usize
+}
+
+// 次に出すブロックの番号 (0..piece_count) を決める
+pub trait Randomizer {
+    fn next(&mut self) -> usize;
+}
+
+// 名前からランダマイザを生成する (未知の名前なら None)
+pub fn create_randomizer(name: &str, seed: u64, piece_names: &[&str]) -> Option<Box<dyn Randomizer>> {
+    let piece_count = piece_names.len();
+
+    // TGM の初期履歴と最初に出さないブロックは S, Z, O を使う (ないセットでは使わない)
+    let position = |name: &str| piece_names.iter().position(|&piece| piece == name);
+    let s_z_o = match (position("S"), position("Z"), position("O")) {
+        (Some(s), Some(z), Some(o)) => Some((s, z, o)),
+        _ => None,
+    };
+
+    let randomizer: Box<dyn Randomizer> = match name {
+        "random" => Box::new(PureRandomizer::new(seed, piece_count)),
+        "7-bag" => Box::new(BagRandomizer::new(seed, piece_count, 1)),
+        "14-bag" => Box::new(BagRandomizer::new(seed, piece_count, 2)),
+        "nes" => Box::new(NesRandomizer::new(seed, piece_count)),
+        "tgm1" => {
+            let (history, excluded) = match s_z_o {
+                Some((s, z, o)) => (vec![z; 4], vec![s, z, o]),
+                None => (Vec::new(), Vec::new()),
+            };
+            Box::new(HistoryRandomizer::new(seed, piece_count, 4, history, excluded))
+        }
+        "tgm2" => {
+            let (history, excluded) = match s_z_o {
+                Some((s, z, o)) => (vec![z, s, s, z], vec![s, z, o]),
+                None => (Vec::new(), Vec::new()),
+            };
+            Box::new(HistoryRandomizer::new(seed, piece_count, 6, history, excluded))
+        }
+        _ => return None,
+    };
+    Some(randomizer)
+}
+
+// 毎回独立に一様な確率で選ぶ
+pub struct PureRandomizer {
+    rng: SplitMixXoshiro256Rng,
+    piece_count: usize,
+}
+
+impl PureRandomizer {
+    pub fn new(seed: u64, piece_count: usize) -> Self {
+        PureRandomizer {
+            rng: SplitMixXoshiro256Rng::new(seed),
+            piece_count,
+        }
+    }
+}
+
+impl Randomizer for PureRandomizer {
+    fn next(&mut self) -> usize {
+        pick(&mut self.rng, self.piece_count)
+    }
+}
+
+// 全種類を copies 個ずつ袋に入れてシャッフルし、順番に配る (7-bag, 14-bag)
+pub struct BagRandomizer {
+    rng: SplitMixXoshiro256Rng,
+    piece_count: usize,
+    copies: usize,
+    bag: Vec<usize>,
+}
+
+impl BagRandomizer {
+    pub fn new(seed: u64, piece_count: usize, copies: usize) -> Self {
+        BagRandomizer {
+            rng: SplitMixXoshiro256Rng::new(seed),
+            piece_count,
+            copies,
+            bag: Vec::new(),
+        }
+    }
+
+    fn refill(&mut self) {
+        self.bag = (0..self.piece_count * self.copies)
+            .map(|i| i % self.piece_count)
+            .collect();
+        self.bag.shuffle(&mut self.rng);
+    }
+}
+
+impl Randomizer for BagRandomizer {
+    fn next(&mut self) -> usize {
+        if self.bag.is_empty() {
+            self.refill();
+        }
+        self.bag.pop().unwrap()
+    }
+}
+
+// NES 方式: 種類数 + 1 の中から選び、直前と同じか余りの値なら1回だけ引き直す
+pub struct NesRandomizer {
+    rng: SplitMixXoshiro256Rng,
+    piece_count: usize,
+    last: Option<usize>,
+}
+
+impl NesRandomizer {
+    pub fn new(seed: u64, piece_count: usize) -> Self {
+        NesRandomizer {
+            rng: SplitMixXoshiro256Rng::new(seed),
+            piece_count,
+            last: None,
+        }
+    }
+}
+
+impl Randomizer for NesRandomizer {
+    fn next(&mut self) -> usize {
+        let mut index = pick(&mut self.rng, self.piece_count + 1);
+        if index == self.piece_count || Some(index) == self.last {
+            index = pick(&mut self.rng, self.piece_count);
+        }
+        self.last = Some(index);
+        index
+    }
+}
+
+// TGM 方式: 直近4個の履歴にあるブロックは rolls 回まで引き直す
+pub struct HistoryRandomizer {
+    rng: SplitMixXoshiro256Rng,
+    piece_count: usize,
+    rolls: usize,
+    history: VecDeque<usize>,
+    first_excluded: Vec<usize>,
+    is_first: bool,
+}
+
+impl HistoryRandomizer {
+    pub fn new(seed: u64, piece_count: usize, rolls: usize, history: Vec<usize>, first_excluded: Vec<usize>) -> Self {
+        HistoryRandomizer {
+            rng: SplitMixXoshiro256Rng::new(seed),
+            piece_count,
+            rolls,
+            history: history.into(),
+            first_excluded,
+            is_first: true,
+        }
+    }
+
+    fn push_history(&mut self, index: usize) {
+        self.history.push_back(index);
+        if self.history.len() > 4 {
+            self.history.pop_front();
+        }
+    }
+}
+
+impl Randomizer for HistoryRandomizer {
+    fn next(&mut self) -> usize {
+        // 最初のブロックは S, Z, O 以外から選ぶ
+        if self.is_first {
+            self.is_first = false;
+            let candidates: Vec<usize> = (0..self.piece_count)
+                .filter(|index| !self.first_excluded.contains(index))
+                .collect();
+            if let Some(&index) = candidates.choose(&mut self.rng) {
+                self.push_history(index);
+                return index;
+            }
+        }
+
+        let mut index = pick(&mut self.rng, self.piece_count);
+        for _ in 1..self.rolls {
+            if !self.history.contains(&index) {
+                break;
+            }
+            index = pick(&mut self.rng, self.piece_count);
+        }
+        self.push_history(index);
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tetris::piece_set::PieceSet;
+
+    use super::*;
+
+    fn get_sequence(name: &str, seed: u64) -> Vec<usize> {
+        let piece_set = PieceSet::standard();
+        let mut randomizer = create_randomizer(name, seed, &piece_set.names()).unwrap();
+        (0..100).map(|_| randomizer.next()).collect()
+    }
+
+    #[test]
+    fn bag_deals_every_piece_once() {
+        let sequence = get_sequence("7-bag", 42);
+        for bag in sequence.chunks_exact(7) {
+            let mut bag = bag.to_vec();
+            bag.sort();
+            assert_eq!(bag, (0..7).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_sequence() {
+        for name in RANDOMIZER_NAMES {
+            let sequence = get_sequence(name, 42);
+            assert_eq!(sequence, get_sequence(name, 42), "{}", name);
+            assert_ne!(sequence, get_sequence(name, 43), "{}", name);
+            assert!(sequence.iter().all(|&index| index < 7), "{}", name);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_name() {
+        assert!(create_randomizer("unknown", 42, &PieceSet::standard().names()).is_none());
+    }
+}
@@ -0,0 +1,101 @@
+use crate::stage::grid::Grid;
+
+use super::tetris_block::TetrisBlock;
+
+// SRS の5番目のキック (TST / Fin の形) で入った場合は Mini の条件でも T-Spin にする
+const TST_KICK_INDEX: usize = 4;
+
+// T の中心 (3x3 の枠の (1, 1)) の四隅を時計回りに並べたもの
+// 向き n の尖った側の2隅は CORNERS[n] と CORNERS[(n + 1) % 4]
+const CORNERS: [(i32, i32); 4] = [(0, 0), (0, 2), (2, 2), (2, 0)];
+
+// ブロックを固定したときの回転の種類
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SpinType {
+    #[default]
+    None,
+    Mini,
+    Full,
+}
+
+// 回転で止まった T の3コーナー判定 (kick_index は 90 度回転で使ったキックの番号)
+pub fn detect_t_spin(grid: &Grid, block: &TetrisBlock, kick_index: Option<usize>) -> SpinType {
+    let occupied: Vec<bool> = CORNERS
+        .iter()
+        .map(|&(row, column)| is_occupied(grid, block.row_offset + row, block.column_offset + column))
+        .collect();
+    if occupied.iter().filter(|&&corner| corner).count() < 3 {
+        return SpinType::None;
+    }
+
+    let state = block.rotation_state % CORNERS.len();
+    let is_front_filled = occupied[state] && occupied[(state + 1) % 4];
+    if is_front_filled || kick_index == Some(TST_KICK_INDEX) {
+        SpinType::Full
+    } else {
+        SpinType::Mini
+    }
+}
+
+// 壁と床はふさがっているものとして扱い、盤面より上は空いているものとする
+fn is_occupied(grid: &Grid, row: i32, column: i32) -> bool {
+    if row < 0 {
+        return column < 0 || column >= grid.columns() as i32;
+    }
+    if row >= grid.rows() as i32 || column < 0 || column >= grid.columns() as i32 {
+        return true;
+    }
+    !grid.is_cell_empty(row as usize, column as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tetris::piece_set::PieceSet;
+
+    use super::*;
+
+    // 尖った側を下に向けた T を左下の隅 (中心は (18, 1)) に置く
+    fn create_t_block() -> TetrisBlock {
+        let piece_set = PieceSet::standard();
+        let index = piece_set.names().iter().position(|&name| name == "T").unwrap();
+        let mut block = piece_set.to_blocks().remove(index);
+        block.rotation_state = 2;
+        block.row_offset = 17;
+        block.column_offset = 0;
+        block
+    }
+
+    fn create_grid(filled: &[(usize, usize)]) -> Grid {
+        let mut grid = Grid::new(20, 10, 0);
+        for &(row, column) in filled {
+            grid.grid[row][column] = 1;
+        }
+        grid
+    }
+
+    #[test]
+    fn detects_full_t_spin() {
+        // 尖った側の2隅と、後ろの1隅がふさがっている
+        let grid = create_grid(&[(17, 0), (19, 0), (19, 2)]);
+        assert_eq!(detect_t_spin(&grid, &create_t_block(), Some(0)), SpinType::Full);
+    }
+
+    #[test]
+    fn detects_mini_t_spin() {
+        // 後ろの2隅と、尖った側の1隅だけがふさがっている
+        let grid = create_grid(&[(17, 0), (17, 2), (19, 0)]);
+        assert_eq!(detect_t_spin(&grid, &create_t_block(), Some(0)), SpinType::Mini);
+    }
+
+    #[test]
+    fn upgrades_mini_with_tst_kick() {
+        let grid = create_grid(&[(17, 0), (17, 2), (19, 0)]);
+        assert_eq!(detect_t_spin(&grid, &create_t_block(), Some(TST_KICK_INDEX)), SpinType::Full);
+    }
+
+    #[test]
+    fn needs_three_corners() {
+        let grid = create_grid(&[(19, 0), (19, 2)]);
+        assert_eq!(detect_t_spin(&grid, &create_t_block(), Some(0)), SpinType::None);
+    }
+}
@@ -1,16 +1,16 @@
 use raylib::prelude::*;
 
+use crate::tetris::piece_set::PieceSet;
+
 pub const DARK_GREY: Color = Color::new(26, 31, 40, 255);
-pub const GREEN: Color = Color::new(47, 230, 23, 255);
-pub const RED: Color = Color::new(232, 18, 18, 255);
-pub const ORANGE: Color = Color::new(226, 116, 17, 255);
-pub const YELLOW: Color = Color::new(237, 234, 4, 255);
-pub const PURPLE: Color = Color::new(166, 0, 247, 255);
-pub const CYAN: Color = Color::new(21, 204, 209, 255);
-pub const BLUE: Color = Color::new(13, 64, 216, 255);
-pub const LIGHT_BLUE: Color = Color::new(59, 85, 162, 255);
-pub const DARK_BLUE: Color = Color::new(44, 44, 127, 255);
+pub const GARBAGE_GREY: Color = Color::new(110, 110, 120, 255);
 
-pub fn get_cell_colors() -> Vec<Color> {
-    vec![DARK_GREY, GREEN, RED, ORANGE, YELLOW, PURPLE, CYAN, BLUE, LIGHT_BLUE, DARK_BLUE]
+// 0 は空のマス、1 以降はピースセットの順に各ブロックの色
+pub fn get_cell_colors(piece_set: &PieceSet) -> Vec<Color> {
+    let mut colors = vec![DARK_GREY];
+    colors.extend(piece_set.pieces.iter().map(|piece| {
+        let [r, g, b] = piece.color;
+        Color::new(r, g, b, 255)
+    }));
+    colors
 }
\ No newline at end of file